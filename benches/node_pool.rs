@@ -0,0 +1,62 @@
+//! Push/pop ping-pong on second::List with and without the node pool, counting allocator calls
+//!
+//! Run with `cargo bench --bench node_pool`
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use too_many_lists::second::List;
+
+struct CountingAlloc;
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static FREES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        FREES.fetch_add(1, Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const ROUNDS: usize = 1_000_000;
+const BURST: u64 = 16;
+
+fn ping_pong(name: &str, mut list: List<u64>) {
+    let allocs = ALLOCS.load(Ordering::Relaxed);
+    let frees = FREES.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for elem in 0..BURST {
+            list.push(black_box(elem));
+        }
+        for _ in 0..BURST {
+            black_box(list.pop());
+        }
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{:<8} {:>10} allocs {:>10} frees {:>8.2?}",
+        name,
+        ALLOCS.load(Ordering::Relaxed) - allocs,
+        FREES.load(Ordering::Relaxed) - frees,
+        elapsed,
+    );
+}
+
+fn main() {
+    ping_pong("plain", List::new());
+    ping_pong("pooled", List::with_node_pool(BURST as usize));
+}
@@ -0,0 +1,69 @@
+/*
+ * C interface for first::List<i32> (src/ffi.rs)
+ *
+ * Hand maintained, keep it in sync with src/ffi.rs.
+ *
+ * Every function except list_new() returns one of the LIST_* status codes below.
+ * Results are written through out pointers.
+ *
+ * Functions can be called from any thread. Calls are serialized by a global lock, so a list
+ * freed by one thread can't be freed from under a call using it on another thread (that call
+ * either finishes first or gets LIST_ERR_INVALID_HANDLE). Freed handle must still not be used
+ * once a new list could have been given its address.
+ */
+#ifndef TOO_MANY_LISTS_FIRST_LIST_H
+#define TOO_MANY_LISTS_FIRST_LIST_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define LIST_OK                  0
+/* handle (or out pointer) is NULL */
+#define LIST_ERR_NULL           -1
+/* handle was never returned by this library or is already freed */
+#define LIST_ERR_INVALID_HANDLE -2
+/* pop on empty list */
+#define LIST_ERR_EMPTY          -3
+/* element to split on isn't in the list */
+#define LIST_ERR_NOT_FOUND      -4
+/* list can't be merged into itself */
+#define LIST_ERR_SAME_LIST      -5
+
+/* opaque handle */
+typedef struct FirstList FirstList;
+
+/* new empty list, release it with list_free() */
+FirstList *list_new(void);
+
+/* frees the list along with its elements, freeing twice gives LIST_ERR_INVALID_HANDLE */
+int list_free(FirstList *list);
+
+/* pushes elem at the head */
+int list_push(FirstList *list, int32_t elem);
+
+/* pops the head into *out, *out is left untouched on LIST_ERR_EMPTY */
+int list_pop(FirstList *list, int32_t *out);
+
+/*
+ * moves the nodes starting from the first one equal to elem into new list written to *out
+ * (NULL on LIST_ERR_NOT_FOUND), new list has to be freed separately
+ */
+int list_split_at(FirstList *list, int32_t elem, FirstList **out);
+
+/*
+ * moves the nodes after the first one equal to elem into new list written to *out
+ * (NULL on LIST_ERR_NOT_FOUND), new list has to be freed separately
+ */
+int list_split_next(FirstList *list, int32_t elem, FirstList **out);
+
+/* appends other to list, on LIST_OK other is consumed and must not be used or freed anymore */
+int list_merge(FirstList *list, FirstList *other);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* TOO_MANY_LISTS_FIRST_LIST_H */
@@ -0,0 +1,417 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Mul, Sub},
+    str::FromStr,
+};
+
+use crate::first::List;
+
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+
+/// Arbitrary-precision unsigned integer, stored as base 10^9 limbs with least significant limb at
+/// the head
+// NOTE: most significant limb (tail) is never 0, that way zero is just the empty list and two
+// equal numbers always have same limbs. Every function making new number goes through
+// from_limbs() (or can't produce the leading zero limb in the first place) to keep it that way
+pub struct BigUint {
+    limbs: List<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseBigUintError {
+    Empty,
+    InvalidDigit,
+}
+
+impl Display for ParseBigUintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseBigUintError::Empty => write!(f, "cannot parse integer from empty string"),
+            ParseBigUintError::InvalidDigit => write!(f, "invalid digit found in string"),
+        }
+    }
+}
+
+impl std::error::Error for ParseBigUintError {}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self { limbs: List::new() }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.peek().is_none()
+    }
+
+    /// Drops the most significant zero limbs
+    fn from_limbs(mut limbs: List<u32>) -> Self {
+        let significant = limbs
+            .iter()
+            .enumerate()
+            .filter(|(_, &limb)| limb != 0)
+            .last()
+            .map_or(0, |(i, _)| i + 1);
+        // NOTE: what is split off is only zero limbs, which get dropped here
+        limbs.split_off(significant);
+        Self { limbs }
+    }
+
+    /// Limbs from the most significant one
+    // NOTE: pushing reverses the order, which is the only way to walk the head first list backwards
+    fn limbs_reversed(&self) -> List<u32> {
+        let mut reversed = List::new();
+        for &limb in &self.limbs {
+            reversed.push(limb);
+        }
+        reversed
+    }
+
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+        let mut limbs = List::new();
+        let mut other_limbs = other.limbs.iter();
+        let mut borrow = 0;
+        for &limb in &self.limbs {
+            let subtrahend = u64::from(*other_limbs.next().unwrap_or(&0)) + borrow;
+            let mut limb = u64::from(limb);
+            borrow = 0;
+            if limb < subtrahend {
+                limb += BASE;
+                borrow = 1;
+            }
+            limbs.push_back((limb - subtrahend) as u32);
+        }
+        Some(Self::from_limbs(limbs))
+    }
+
+    pub fn mul_small(&self, factor: u32) -> BigUint {
+        if factor == 0 {
+            return Self::zero();
+        }
+        let mut limbs = List::new();
+        let mut carry = 0;
+        for &limb in &self.limbs {
+            let product = u64::from(limb) * u64::from(factor) + carry;
+            limbs.push_back((product % BASE) as u32);
+            carry = product / BASE;
+        }
+        while carry != 0 {
+            limbs.push_back((carry % BASE) as u32);
+            carry /= BASE;
+        }
+        Self { limbs }
+    }
+
+    /// Returns quotient and remainder
+    ///
+    /// Panics if `divisor` is 0
+    pub fn div_rem_small(&self, divisor: u32) -> (BigUint, u32) {
+        assert!(divisor != 0, "attempt to divide by zero");
+        let divisor = u64::from(divisor);
+        let mut most_significant_first = self.limbs_reversed();
+        let mut quotient = List::new();
+        let mut remainder = 0;
+        // NOTE: quotient limbs come out from the most significant one, so pushing them at the head
+        // gives the least significant one at the head in the end
+        while let Some(limb) = most_significant_first.pop() {
+            let current = remainder * BASE + u64::from(limb);
+            remainder = current % divisor;
+            let limb = current / divisor;
+            // skip leading zeros
+            if limb != 0 || quotient.peek().is_some() {
+                quotient.push(limb as u32);
+            }
+        }
+        (Self { limbs: quotient }, remainder as u32)
+    }
+}
+
+impl Default for BigUint {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl Clone for BigUint {
+    fn clone(&self) -> Self {
+        let mut limbs = List::new();
+        for &limb in &self.limbs {
+            limbs.push_back(limb);
+        }
+        Self { limbs }
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(mut value: u64) -> Self {
+        let mut limbs = List::new();
+        while value != 0 {
+            limbs.push_back((value % BASE) as u32);
+            value /= BASE;
+        }
+        Self { limbs }
+    }
+}
+
+impl PartialEq for BigUint {
+    fn eq(&self, other: &Self) -> bool {
+        self.limbs.iter().eq(other.limbs.iter())
+    }
+}
+
+impl Eq for BigUint {}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    // NOTE: walks both from the least significant limb, so the last limbs which differ decide the
+    // order, unless one number has more limbs, which makes it bigger anyway
+    fn cmp(&self, other: &Self) -> Ordering {
+        let mut a = self.limbs.iter();
+        let mut b = other.limbs.iter();
+        let mut ordering = Ordering::Equal;
+        loop {
+            match (a.next(), b.next()) {
+                (Some(x), Some(y)) => {
+                    if x != y {
+                        ordering = x.cmp(y);
+                    }
+                },
+                (Some(_), None) => return Ordering::Greater,
+                (None, Some(_)) => return Ordering::Less,
+                (None, None) => return ordering,
+            }
+        }
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let mut limbs = List::new();
+        let mut a = self.limbs.iter();
+        let mut b = other.limbs.iter();
+        let mut carry = 0;
+        loop {
+            let (x, y) = match (a.next(), b.next()) {
+                (None, None) => break,
+                (x, y) => (x.copied().unwrap_or(0), y.copied().unwrap_or(0)),
+            };
+            let sum = u64::from(x) + u64::from(y) + carry;
+            limbs.push_back((sum % BASE) as u32);
+            carry = sum / BASE;
+        }
+        if carry != 0 {
+            limbs.push_back(carry as u32);
+        }
+        BigUint { limbs }
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: BigUint) -> BigUint {
+        &self + &other
+    }
+}
+
+impl Sub for &BigUint {
+    type Output = BigUint;
+
+    /// Panics if `other` is bigger, see checked_sub() for non panicking version
+    fn sub(self, other: &BigUint) -> BigUint {
+        self.checked_sub(other).expect("attempt to subtract with overflow")
+    }
+}
+
+impl Sub for BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: BigUint) -> BigUint {
+        &self - &other
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    // NOTE: schoolbook multiplication is adding up the shifted partial products
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut product = BigUint::zero();
+        for (shift, &limb) in self.limbs.iter().enumerate() {
+            let mut partial = other.mul_small(limb);
+            if partial.is_zero() {
+                continue;
+            }
+            // multiplying by BASE^shift is just pushing zero limbs at the head
+            for _ in 0..shift {
+                partial.limbs.push(0);
+            }
+            product = &product + &partial;
+        }
+        product
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: BigUint) -> BigUint {
+        &self * &other
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let most_significant_first = self.limbs_reversed();
+        let mut limbs = most_significant_first.iter();
+        let mut digits = match limbs.next() {
+            None => "0".to_string(),
+            Some(limb) => limb.to_string(),
+        };
+        for limb in limbs {
+            digits.push_str(&format!("{:0width$}", limb, width = BASE_DIGITS));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl std::fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigUintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseBigUintError::Empty);
+        }
+        if !s.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(ParseBigUintError::InvalidDigit);
+        }
+        // chunks of 9 digits from the end of the string are the limbs from the least significant
+        let mut limbs = List::new();
+        let mut end = s.len();
+        while end > 0 {
+            let start = end.saturating_sub(BASE_DIGITS);
+            limbs.push_back(s[start..end].parse().map_err(|_| ParseBigUintError::InvalidDigit)?);
+            end = start;
+        }
+        Ok(Self::from_limbs(limbs))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BigUint, ParseBigUintError};
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_display() {
+        assert_eq!(big("0").to_string(), "0");
+        assert_eq!(big("0000").to_string(), "0");
+        assert_eq!(big("000123").to_string(), "123");
+        assert_eq!(big("1000000000").to_string(), "1000000000");
+        assert_eq!(big("123000000000000000001").to_string(), "123000000000000000001");
+        assert_eq!(format!("{:>5}", big("42")), "   42");
+        assert_eq!("".parse::<BigUint>(), Err(ParseBigUintError::Empty));
+        assert_eq!("12a".parse::<BigUint>(), Err(ParseBigUintError::InvalidDigit));
+        assert_eq!("+1".parse::<BigUint>(), Err(ParseBigUintError::InvalidDigit));
+        assert_eq!(BigUint::from(u64::MAX).to_string(), u64::MAX.to_string());
+    }
+
+    #[test]
+    fn test_add_sub() {
+        let a = BigUint::from(u64::MAX);
+        let b = BigUint::from(1);
+        assert_eq!((&a + &b).to_string(), (u128::from(u64::MAX) + 1).to_string());
+        assert_eq!(&(&a + &b) - &b, a);
+        assert_eq!(&a - &a, BigUint::zero());
+        assert!((&a - &a).is_zero());
+        assert_eq!(b.checked_sub(&a), None);
+
+        let a = big("1000000000000000000000000000");
+        assert_eq!((&a - &b).to_string(), "999999999999999999999999999");
+        assert_eq!((a + BigUint::zero()).to_string(), "1000000000000000000000000000");
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to subtract with overflow")]
+    fn test_sub_overflow() {
+        let _ = BigUint::from(1) - BigUint::from(2);
+    }
+
+    #[test]
+    fn test_mul() {
+        let mut power = BigUint::from(1);
+        for _ in 0..128 {
+            power = power.mul_small(2);
+        }
+        assert_eq!(power.to_string(), "340282366920938463463374607431768211456");
+
+        let mut factorial = BigUint::from(1);
+        for n in 1..=50 {
+            factorial = &factorial * &BigUint::from(n);
+        }
+        assert_eq!(
+            factorial.to_string(),
+            "30414093201713378043612608166064768844377641568960512000000000000"
+        );
+
+        let a = BigUint::from(u64::MAX);
+        let square = u128::from(u64::MAX) * u128::from(u64::MAX);
+        assert_eq!((&a * &a).to_string(), square.to_string());
+        assert!((&a * &BigUint::zero()).is_zero());
+        assert!(a.mul_small(0).is_zero());
+    }
+
+    #[test]
+    fn test_div_rem_small() {
+        let a = big("340282366920938463463374607431768211457");
+        let (quotient, remainder) = a.div_rem_small(2);
+        assert_eq!(quotient.to_string(), "170141183460469231731687303715884105728");
+        assert_eq!(remainder, 1);
+
+        let (quotient, remainder) = BigUint::from(5).div_rem_small(7);
+        assert!(quotient.is_zero());
+        assert_eq!(remainder, 5);
+
+        // back to decimal digits one by one
+        let mut a = big("9876543210123456789");
+        let mut digits = Vec::new();
+        while !a.is_zero() {
+            let (quotient, digit) = a.div_rem_small(10);
+            digits.push(digit);
+            a = quotient;
+        }
+        digits.reverse();
+        assert_eq!(digits, vec![9, 8, 7, 6, 5, 4, 3, 2, 1, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_cmp() {
+        assert!(big("1000000000") > big("999999999"));
+        assert!(big("2000000001") > big("1000000002"));
+        assert!(big("1000000002") < big("2000000001"));
+        assert!(big("0") < big("1"));
+        assert_eq!(big("0012"), big("12"));
+        assert_eq!(big("5").cmp(&big("5")), std::cmp::Ordering::Equal);
+    }
+}
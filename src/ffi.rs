@@ -0,0 +1,321 @@
+//! C ABI for first::List<i32>, see include/first_list.h for the C side of it
+//!
+//! Every function returns one of the `LIST_*` status codes, and results (if any) are written
+//! through out pointers.
+
+use std::{
+    collections::BTreeSet,
+    ffi::c_int,
+    ptr,
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::first::List;
+
+pub const LIST_OK: c_int = 0;
+/// handle (or out pointer) is null
+pub const LIST_ERR_NULL: c_int = -1;
+/// handle was never returned by this library or is already freed
+pub const LIST_ERR_INVALID_HANDLE: c_int = -2;
+/// pop on empty list
+pub const LIST_ERR_EMPTY: c_int = -3;
+/// element to split on isn't in the list
+pub const LIST_ERR_NOT_FOUND: c_int = -4;
+/// list can't be merged into itself
+pub const LIST_ERR_SAME_LIST: c_int = -5;
+
+pub type FirstList = List<i32>;
+
+// NOTE: handles are just boxed lists, and every live one is kept here so that use after free and
+// double free can be caught instead of touching freed memory. Every function holds the lock for as
+// long as it uses the handle, so a list can't be freed by another thread in the middle of it (and
+// calls on the same list from different threads are serialized). This can't catch a stale handle
+// whose address got reused by a newer list though
+static LIVE_LISTS: Mutex<BTreeSet<usize>> = Mutex::new(BTreeSet::new());
+
+type LiveLists = MutexGuard<'static, BTreeSet<usize>>;
+
+fn live_lists() -> LiveLists {
+    // NOTE: nothing panics while holding the lock, but don't let a poisoned lock leak panic into C
+    LIVE_LISTS.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn register(live: &mut LiveLists, list: FirstList) -> *mut FirstList {
+    let handle = Box::into_raw(Box::new(list));
+    live.insert(handle as usize);
+    handle
+}
+
+fn check(live: &LiveLists, list: *mut FirstList) -> c_int {
+    if list.is_null() {
+        LIST_ERR_NULL
+    } else if !live.contains(&(list as usize)) {
+        LIST_ERR_INVALID_HANDLE
+    } else {
+        LIST_OK
+    }
+}
+
+/// Checks the handle and runs `op` on its list, with the registry locked till `op` returns
+unsafe fn with_list(
+    list: *mut FirstList,
+    op: impl FnOnce(&mut LiveLists, &mut FirstList) -> c_int,
+) -> c_int {
+    let mut live = live_lists();
+    let status = check(&live, list);
+    if status != LIST_OK {
+        return status;
+    }
+    op(&mut live, &mut *list)
+}
+
+/// Returns new empty list, to be released with list_free()
+#[no_mangle]
+pub extern "C" fn list_new() -> *mut FirstList {
+    register(&mut live_lists(), List::new())
+}
+
+/// Frees the list along with all of its elements
+///
+/// # Safety
+/// `list` must be null or a pointer returned by this library
+#[no_mangle]
+pub unsafe extern "C" fn list_free(list: *mut FirstList) -> c_int {
+    if list.is_null() {
+        return LIST_ERR_NULL;
+    }
+    if !live_lists().remove(&(list as usize)) {
+        return LIST_ERR_INVALID_HANDLE;
+    }
+    drop(Box::from_raw(list));
+    LIST_OK
+}
+
+/// # Safety
+/// `list` must be null or a pointer returned by this library
+#[no_mangle]
+pub unsafe extern "C" fn list_push(list: *mut FirstList, elem: i32) -> c_int {
+    with_list(list, |_, list| {
+        list.push(elem);
+        LIST_OK
+    })
+}
+
+/// Pops the head into `out`, `out` is left untouched if list is empty
+///
+/// # Safety
+/// `list` must be null or a pointer returned by this library, `out` must be null or valid for
+/// writing an int32_t
+#[no_mangle]
+pub unsafe extern "C" fn list_pop(list: *mut FirstList, out: *mut i32) -> c_int {
+    with_list(list, |_, list| {
+        if out.is_null() {
+            return LIST_ERR_NULL;
+        }
+        match list.pop() {
+            Some(elem) => {
+                *out = elem;
+                LIST_OK
+            },
+            None => LIST_ERR_EMPTY,
+        }
+    })
+}
+
+/// Splits the list at (see first::List::split_at) or after (see first::List::split_next) the
+/// matching node and hands the new list out through `out`
+unsafe fn split_with(
+    list: *mut FirstList,
+    out: *mut *mut FirstList,
+    split: impl FnOnce(&mut FirstList) -> Option<FirstList>,
+) -> c_int {
+    with_list(list, |live, list| {
+        if out.is_null() {
+            return LIST_ERR_NULL;
+        }
+        match split(list) {
+            Some(new_list) => {
+                *out = register(live, new_list);
+                LIST_OK
+            },
+            None => {
+                *out = ptr::null_mut();
+                LIST_ERR_NOT_FOUND
+            },
+        }
+    })
+}
+
+/// Moves the nodes starting from the first one matching `elem` into new list written to `out`
+/// (null if no node matched). New list has to be freed separately
+///
+/// # Safety
+/// `list` must be null or a pointer returned by this library, `out` must be null or valid for
+/// writing a pointer
+#[no_mangle]
+pub unsafe extern "C" fn list_split_at(
+    list: *mut FirstList,
+    elem: i32,
+    out: *mut *mut FirstList,
+) -> c_int {
+    split_with(list, out, |list| list.split_at(elem))
+}
+
+/// Moves the nodes after the first one matching `elem` into new list written to `out` (null if no
+/// node matched). New list has to be freed separately
+///
+/// # Safety
+/// `list` must be null or a pointer returned by this library, `out` must be null or valid for
+/// writing a pointer
+#[no_mangle]
+pub unsafe extern "C" fn list_split_next(
+    list: *mut FirstList,
+    elem: i32,
+    out: *mut *mut FirstList,
+) -> c_int {
+    split_with(list, out, |list| list.split_next(elem))
+}
+
+/// Appends `other` to `list`. On success `other` is consumed and must not be used (or freed)
+/// anymore
+///
+/// # Safety
+/// `list` and `other` must be null or pointers returned by this library
+#[no_mangle]
+pub unsafe extern "C" fn list_merge(list: *mut FirstList, other: *mut FirstList) -> c_int {
+    let mut live = live_lists();
+    for handle in [list, other] {
+        let status = check(&live, handle);
+        if status != LIST_OK {
+            return status;
+        }
+    }
+    if list == other {
+        return LIST_ERR_SAME_LIST;
+    }
+    live.remove(&(other as usize));
+    (*list).merge(*Box::from_raw(other));
+    LIST_OK
+}
+
+#[cfg(test)]
+mod test {
+    use std::{ptr, sync::Mutex};
+
+    use super::*;
+
+    // NOTE: freed handle's address can be handed out again by list_new() from a test running in
+    // parallel, which would make it valid again. Running these one at a time avoids that
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    unsafe fn drain(list: *mut FirstList) -> Vec<i32> {
+        let mut elems = Vec::new();
+        let mut elem = 0;
+        while list_pop(list, &mut elem) == LIST_OK {
+            elems.push(elem);
+        }
+        elems
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+        unsafe {
+            let list = list_new();
+            let mut elem = 0;
+            assert_eq!(list_pop(list, &mut elem), LIST_ERR_EMPTY);
+            for elem in 1..=3 {
+                assert_eq!(list_push(list, elem), LIST_OK);
+            }
+            assert_eq!(list_pop(list, &mut elem), LIST_OK);
+            assert_eq!(elem, 3);
+            assert_eq!(drain(list), vec![2, 1]);
+            assert_eq!(list_free(list), LIST_OK);
+        }
+    }
+
+    #[test]
+    fn test_split_merge() {
+        let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+        unsafe {
+            let list = list_new();
+            for elem in 1..=5 {
+                list_push(list, elem);
+            }
+
+            // out is reset to null when nothing matched
+            let mut out = list;
+            assert_eq!(list_split_at(list, 10, &mut out), LIST_ERR_NOT_FOUND);
+            assert!(out.is_null());
+
+            let mut half = ptr::null_mut();
+            assert_eq!(list_split_at(list, 3, &mut half), LIST_OK);
+            let mut rest = ptr::null_mut();
+            assert_eq!(list_split_next(half, 2, &mut rest), LIST_OK);
+
+            assert_eq!(list_merge(list, list), LIST_ERR_SAME_LIST);
+            assert_eq!(list_merge(list, rest), LIST_OK);
+            // rest is consumed by merge
+            assert_eq!(list_free(rest), LIST_ERR_INVALID_HANDLE);
+
+            assert_eq!(drain(list), vec![5, 4, 1]);
+            assert_eq!(drain(half), vec![3, 2]);
+            assert_eq!(list_free(list), LIST_OK);
+            assert_eq!(list_free(half), LIST_OK);
+        }
+    }
+
+    #[test]
+    fn test_null_and_double_free() {
+        let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+        unsafe {
+            let mut elem = 0;
+            let mut out = ptr::null_mut();
+            assert_eq!(list_free(ptr::null_mut()), LIST_ERR_NULL);
+            assert_eq!(list_push(ptr::null_mut(), 1), LIST_ERR_NULL);
+            assert_eq!(list_pop(ptr::null_mut(), &mut elem), LIST_ERR_NULL);
+            assert_eq!(list_split_at(ptr::null_mut(), 1, &mut out), LIST_ERR_NULL);
+            assert_eq!(list_split_next(ptr::null_mut(), 1, &mut out), LIST_ERR_NULL);
+
+            let list = list_new();
+            list_push(list, 1);
+            assert_eq!(list_pop(list, ptr::null_mut()), LIST_ERR_NULL);
+            assert_eq!(list_split_at(list, 1, ptr::null_mut()), LIST_ERR_NULL);
+            assert_eq!(list_merge(list, ptr::null_mut()), LIST_ERR_NULL);
+            assert_eq!(list_merge(ptr::null_mut(), list), LIST_ERR_NULL);
+
+            assert_eq!(list_free(list), LIST_OK);
+            assert_eq!(list_free(list), LIST_ERR_INVALID_HANDLE);
+            assert_eq!(list_push(list, 1), LIST_ERR_INVALID_HANDLE);
+            assert_eq!(list_pop(list, &mut elem), LIST_ERR_INVALID_HANDLE);
+
+            // never returned by the library
+            let mut local = List::new();
+            assert_eq!(list_push(&mut local, 1), LIST_ERR_INVALID_HANDLE);
+        }
+    }
+
+    #[test]
+    fn test_threads() {
+        let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+        // NOTE: raw pointers aren't Send, so the handle is passed around as an address
+        let list = list_new() as usize;
+        let threads = (0..4)
+            .map(|_| {
+                std::thread::spawn(move || {
+                    for elem in 0..100 {
+                        unsafe { list_push(list as *mut FirstList, elem) };
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        unsafe {
+            let list = list as *mut FirstList;
+            assert_eq!(drain(list).len(), 400);
+            assert_eq!(list_free(list), LIST_OK);
+        }
+    }
+}
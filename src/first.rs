@@ -1,112 +1,713 @@
-use std::mem;
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    io::{self, Read, Write},
+    marker::PhantomData,
+    mem,
+    ptr::NonNull,
+};
 
-pub struct List {
-    head: Link,
+// NOTE: tail is a pointer to the last node (None when list is empty). It can't be a reference
+// (or a Box) because the last node is already owned by the second last node's Link, and aliasing
+// it with safe pointer isn't allowed. For the same reason nodes are not owned by Boxes either:
+// moving a Box asserts unique access to its node, which invalidates the tail pointer taken before
+// the move. So nodes are allocated with Box::into_raw() and linked through raw pointers, and only
+// turned back into a Box (Box::from_raw()) when they are unlinked for good in pop().
+// NonNull is used rather than *mut because *mut would make List invariant in T, while a chain of
+// Boxes (and so List) is covariant.
+// Every function that changes the shape of the chain has to keep tail pointing at the last node,
+// or else merge/push_back write through dangling pointer
+pub struct List<T> {
+    head: Link<T>,
+    tail: Option<NonNull<Node<T>>>,
+    // NOTE: tells the compiler that the list owns (and drops) nodes holding T
+    _nodes: PhantomData<Box<Node<T>>>,
 }
+// NOTE: raw pointers make List neither Send nor Sync, but they only ever point to the nodes owned
+// by the same list, so it is as thread-safe as a Box chain would be
+unsafe impl<T: Send> Send for List<T> {}
+unsafe impl<T: Sync> Sync for List<T> {}
 
-struct Node {
-    elem: i32,
-    next: Link,
+struct Node<T> {
+    elem: T,
+    next: Link<T>,
 }
 
-enum Link {
+enum Link<T> {
     Empty,
-    More(Box<Node>),
+    More(NonNull<Node<T>>),
 }
 
-impl List {
+// NOTE: derive would require T: Copy, link is just a pointer whatever T is
+impl<T> Clone for Link<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Link<T> {}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> List<T> {
     pub fn new() -> Self {
-        Self { head: Link::Empty }
+        Self {
+            head: Link::Empty,
+            tail: None,
+            _nodes: PhantomData,
+        }
     }
 
-    pub fn push(&mut self, elem: i32) {
-        // NOTE: we will not be missing all the previous nodes added to list
-        // because mem::replace will return dest which was before replacement
-        let node = Node {
-            elem,
-            next: mem::replace(&mut self.head, Link::Empty),
+    /// Makes a list out of the chain detached from other list, `tail` being the tail of the list
+    /// chain is detached from (which is also the tail of detached part, if there's any node in it)
+    fn from_detached(head: Link<T>, tail: Option<NonNull<Node<T>>>) -> Self {
+        let tail = match head {
+            Link::Empty => None,
+            Link::More(_) => tail,
         };
-        self.head = Link::More(Box::new(node));
+        Self {
+            head,
+            tail,
+            _nodes: PhantomData,
+        }
+    }
+
+    /// Allocates unlinked node, which is freed by pop() (or drop) once it's linked to a list
+    fn new_node(elem: T) -> NonNull<Node<T>> {
+        let node = Box::new(Node {
+            elem,
+            next: Link::Empty,
+        });
+        // SAFETY: Box is never null
+        unsafe { NonNull::new_unchecked(Box::into_raw(node)) }
+    }
+
+    pub fn push(&mut self, elem: T) {
+        self.push_node(Self::new_node(elem));
+    }
+
+    /// Links already allocated (and unlinked) node before the head
+    fn push_node(&mut self, node: NonNull<Node<T>>) {
+        // SAFETY: node is allocated and not linked to any list, so nothing else accesses it
+        unsafe {
+            (*node.as_ptr()).next = self.head;
+        }
+        // first node is the tail too
+        if self.tail.is_none() {
+            self.tail = Some(node);
+        }
+        self.head = Link::More(node);
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        self.push_back_node(Self::new_node(elem));
+    }
+
+    /// Links already allocated (and unlinked) node after the tail
+    fn push_back_node(&mut self, node: NonNull<Node<T>>) {
+        match self.tail {
+            None => self.head = Link::More(node),
+            // SAFETY: tail points to the last node owned by this list
+            Some(tail) => unsafe {
+                (*tail.as_ptr()).next = Link::More(node);
+            },
+        }
+        self.tail = Some(node);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        // NOTE: T may not be Copy, so elem is moved out of the box instead of copied
+        // SAFETY: unlinked node isn't reachable from the list anymore, so it can be freed
+        self.pop_node().map(|node| unsafe { Box::from_raw(node.as_ptr()) }.elem)
     }
 
-    pub fn pop(&mut self) -> Option<i32> {
-        match mem::replace(&mut self.head, Link::Empty) {
+    /// Unlinks the head node without deallocating it
+    fn pop_node(&mut self) -> Option<NonNull<Node<T>>> {
+        match self.head {
             Link::Empty => None,
             Link::More(node) => {
-                let result = node.elem;
-                self.head = node.next;
-                Some(result)
+                // SAFETY: head is a node owned by this list
+                unsafe {
+                    self.head = mem::replace(&mut (*node.as_ptr()).next, Link::Empty);
+                }
+                if let Link::Empty = self.head {
+                    self.tail = None;
+                }
+                Some(node)
             }
         }
     }
-    
+
+    pub fn peek(&self) -> Option<&T> {
+        match self.head {
+            Link::Empty => None,
+            // SAFETY: head is a node owned by this list, borrowed as long as the list is
+            Link::More(node) => Some(unsafe { &(*node.as_ptr()).elem }),
+        }
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        match self.head {
+            Link::Empty => None,
+            // SAFETY: head is a node owned by this list, borrowed as long as the list is
+            Link::More(node) => Some(unsafe { &mut (*node.as_ptr()).elem }),
+        }
+    }
+}
+
+// NOTE: the splits below walk the chain through the nodes' Links as raw pointers, so every node
+// they touch is one owned by this list, and nothing else borrows it while the list is borrowed
+// mutably. That's what all of their SAFETY comments come down to
+
+// NOTE: equality is only needed by the functions which split on matching element, rest of the list
+// works for any T
+impl<T: PartialEq> List<T> {
     /// Split on the basis of element match
     /// Returns the new list from the next node of the node which matched the elem provided as
     /// argument
-    pub fn split_next(&mut self, elem: i32) -> Option<List> {
+    pub fn split_next(&mut self, elem: T) -> Option<List<T>> {
         let mut current = &mut self.head;
-        while let Link::More(ref mut node) = current {
-            if node.elem == elem {
-                let mut list = List::new();
-                list.head = mem::replace(&mut node.next, Link::Empty);
+        while let Link::More(node) = *current {
+            // SAFETY: node is owned by this list
+            let node_ref = unsafe { &mut *node.as_ptr() };
+            if node_ref.elem == elem {
+                let detached = mem::replace(&mut node_ref.next, Link::Empty);
+                let list = List::from_detached(detached, self.tail);
+                self.tail = Some(node);
                 return Some(list);
             } 
-            current = &mut node.next;
+            current = &mut node_ref.next;
         }
         None
     }
 
     // NOTE: why split_at() is written this way ? You'll get to know once you reach second
     // list(second.rs)
-    pub fn split_at(&mut self, elem: i32) -> Option<List> {
+    pub fn split_at(&mut self, elem: T) -> Option<List<T>> {
+        // NOTE: prev is the node owning next_node, which becomes the tail after splitting
+        let mut prev = None;
         let mut next_node = &mut self.head;
         loop {
-            match next_node {
-                Link::More(node) if node.elem == elem => {
-                    let mut list = List::new();
-                    list.head = mem::replace(&mut next_node, Link::Empty);
+            match *next_node {
+                // SAFETY: node is owned by this list
+                Link::More(node) if unsafe { node.as_ref() }.elem == elem => {
+                    let list = List::from_detached(mem::replace(next_node, Link::Empty), self.tail);
+                    self.tail = prev;
                     break Some(list);
                 },
-                Link::More(node) => next_node = &mut node.next,
+                Link::More(node) => {
+                    prev = Some(node);
+                    // SAFETY: node is owned by this list
+                    next_node = unsafe { &mut (*node.as_ptr()).next };
+                },
                 Link::Empty => break None,
             }
         }
     }
+}
 
-    pub fn merge(&mut self, mut list: List) {
-        match self.head {
-            Link::Empty => {
-                self.head = mem::replace(&mut list.head, Link::Empty);
+impl<T> List<T> {
+    /// Split on the basis of predicate
+    /// Returns the new list starting from the first node for which the predicate returned true
+    pub fn split_when<F>(&mut self, mut pred: F) -> Option<List<T>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        // NOTE: prev is the node owning next_node, which becomes the tail after splitting
+        let mut prev = None;
+        let mut next_node = &mut self.head;
+        loop {
+            match *next_node {
+                // SAFETY: node is owned by this list
+                Link::More(node) if pred(&unsafe { node.as_ref() }.elem) => {
+                    let list = List::from_detached(mem::replace(next_node, Link::Empty), self.tail);
+                    self.tail = prev;
+                    break Some(list);
+                },
+                Link::More(node) => {
+                    prev = Some(node);
+                    // SAFETY: node is owned by this list
+                    next_node = unsafe { &mut (*node.as_ptr()).next };
+                },
+                Link::Empty => break None,
+            }
+        }
+    }
+
+    /// Split on the basis of predicate
+    /// Returns the new list from the next node of the first node for which the predicate returned
+    /// true
+    pub fn split_after_when<F>(&mut self, mut pred: F) -> Option<List<T>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut current = &mut self.head;
+        while let Link::More(node) = *current {
+            // SAFETY: node is owned by this list
+            let node_ref = unsafe { &mut *node.as_ptr() };
+            if pred(&node_ref.elem) {
+                let detached = mem::replace(&mut node_ref.next, Link::Empty);
+                let list = List::from_detached(detached, self.tail);
+                self.tail = Some(node);
+                return Some(list);
+            }
+            current = &mut node_ref.next;
+        }
+        None
+    }
+
+    /// Split on the basis of position
+    /// Returns the new list starting from the node at `index` (0 being head), leaving the first
+    /// `index` nodes in this list. Returns None if list has less than `index` nodes (splitting at
+    /// the length gives an empty list, like Vec::split_off)
+    pub fn split_off(&mut self, index: usize) -> Option<List<T>> {
+        let mut prev = None;
+        let mut next_node = &mut self.head;
+        for _ in 0..index {
+            match *next_node {
+                Link::More(node) => {
+                    prev = Some(node);
+                    // SAFETY: node is owned by this list
+                    next_node = unsafe { &mut (*node.as_ptr()).next };
+                },
+                Link::Empty => return None,
+            }
+        }
+        let list = List::from_detached(mem::replace(next_node, Link::Empty), self.tail);
+        self.tail = prev;
+        Some(list)
+    }
+
+    /// Breaks the list into chunks of `n` nodes each (from head), the last chunk being shorter if
+    /// length isn't a multiple of `n`
+    ///
+    /// Panics if `n` is 0
+    pub fn split_every(self, n: usize) -> Vec<List<T>> {
+        assert!(n != 0, "chunk size must be non-zero");
+        let mut chunks = Vec::new();
+        let mut current = self;
+        while let Link::More(_) = current.head {
+            match current.split_off(n) {
+                Some(rest) => {
+                    chunks.push(current);
+                    current = rest;
+                },
+                None => {
+                    // less than n nodes remaining, this is the last chunk
+                    chunks.push(current);
+                    break;
+                },
+            }
+        }
+        chunks
+    }
+
+    /// Appends `list` at the end of this list in constant time
+    pub fn merge(&mut self, mut list: List<T>) {
+        let head = mem::replace(&mut list.head, Link::Empty);
+        if let Link::Empty = head {
+            return;
+        }
+        match self.tail {
+            None => self.head = head,
+            // SAFETY: tail points to the last node owned by this list
+            Some(tail) => unsafe {
+                (*tail.as_ptr()).next = head;
             },
-            Link::More(_) => {
-                let mut next_node = &mut self.head;
-                while let Link::More(node) = next_node {
-                    match node.next {
-                        Link::Empty => {
-                            node.next = mem::replace(&mut list.head, Link::Empty);
-                            break;
-                        },
-                        Link::More(_) => next_node = &mut node.next,
-                    }
-                }
+        }
+        self.tail = list.tail.take();
+    }
+}
+
+impl<T> List<T> {
+    /// Sorts the list in ascending order, see sort_by()
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    /// Sorts the list by the key extracted from each element, see sort_by()
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    /// Stable merge sort which relinks the existing nodes, nothing gets allocated or copied
+    // NOTE: this is bottom-up merge sort without recursion. bins[i] is either empty or holds a
+    // sorted run of 2^i nodes, and adding a node to the bins works like incrementing a binary
+    // counter (merge carry with every full bin on the way). So extra space is just the 64 runs no
+    // matter how long the list is.
+    // Higher bins always hold the earlier nodes, and merge_runs() prefers first run on ties,
+    // that's what keeps the sort stable
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut bins: [List<T>; usize::BITS as usize] = std::array::from_fn(|_| List::new());
+        while let Some(node) = self.pop_node() {
+            let mut carry = List::new();
+            carry.push_back_node(node);
+            let mut i = 0;
+            while let Link::More(_) = bins[i].head {
+                carry = Self::merge_runs(mem::take(&mut bins[i]), carry, &mut compare);
+                i += 1;
+            }
+            bins[i] = carry;
+        }
+        for bin in bins.iter_mut() {
+            let sorted = mem::take(self);
+            *self = Self::merge_runs(mem::take(bin), sorted, &mut compare);
+        }
+    }
+
+    /// Merges two sorted runs, `first` holding the nodes which came earlier in the list
+    fn merge_runs<F>(mut first: List<T>, mut second: List<T>, compare: &mut F) -> List<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut merged = List::new();
+        while let (Some(a), Some(b)) = (first.peek(), second.peek()) {
+            let run = if compare(b, a) == Ordering::Less {
+                &mut second
+            } else {
+                &mut first
+            };
+            if let Some(node) = run.pop_node() {
+                merged.push_back_node(node);
             }
         }
+        // at most one of them has nodes left, which are already sorted
+        merged.merge(first);
+        merged.merge(second);
+        merged
     }
 }
 
-impl Drop for List {
-    fn drop(&mut self) {
-        let mut current = mem::replace(&mut self.head, Link::Empty);
-        while let Link::More(ref mut boxed_node) = current {
-            current = mem::replace(&mut boxed_node.next, Link::Empty);
-            // boxed_node gets dropped here
+// NOTE: all of these only relink the nodes (mostly by pop_node()/push_node() and split/merge), no
+// element is moved out of its node
+impl<T> List<T> {
+    pub fn reverse(&mut self) {
+        let mut reversed = List::new();
+        while let Some(node) = self.pop_node() {
+            reversed.push_node(node);
+        }
+        *self = reversed;
+    }
+
+    /// Moves first `k` nodes to the back of the list, `k` wraps around the length of the list
+    pub fn rotate_left(&mut self, k: usize) {
+        let len = self.iter().count();
+        if len == 0 || k.is_multiple_of(len) {
+            return;
+        }
+        if let Some(mut rotated) = self.split_off(k % len) {
+            rotated.merge(mem::take(self));
+            *self = rotated;
         }
     }
+
+    /// Moves last `k` nodes to the front of the list, `k` wraps around the length of the list
+    pub fn rotate_right(&mut self, k: usize) {
+        let len = self.iter().count();
+        if len == 0 {
+            return;
+        }
+        self.rotate_left(len - k % len);
+    }
+
+    /// Reverses every consecutive group of `k` nodes, trailing nodes which are less than `k` are
+    /// left as they are
+    ///
+    /// Panics if `k` is 0
+    pub fn reverse_groups(&mut self, k: usize) {
+        assert!(k != 0, "group size must be non-zero");
+        let mut rest = mem::take(self);
+        // NOTE: split_off() gives None when there are less than k nodes left
+        while let Some(next_rest) = rest.split_off(k) {
+            rest.reverse();
+            self.merge(rest);
+            rest = next_rest;
+        }
+        self.merge(rest);
+    }
+}
+
+pub struct IntoIter<T>(List<T>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+}
+
+impl<T> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+// NOTE: iterators keep an Option of node reference as cursor, converting the Link to it by
+// matching. References are made from the node pointers, and they borrow the list for as long as
+// the iterator does
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<T> List<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: match self.head {
+                Link::Empty => None,
+                // SAFETY: head is a node owned by this list
+                Link::More(node) => Some(unsafe { &*node.as_ptr() }),
+            },
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            self.next = match node.next {
+                Link::Empty => None,
+                // SAFETY: every node linked from the list is owned by it
+                Link::More(node) => Some(unsafe { &*node.as_ptr() }),
+            };
+            &node.elem
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IterMut<'a, T> {
+    next: Option<&'a mut Node<T>>,
+}
+
+impl<T> List<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: match self.head {
+                Link::Empty => None,
+                // SAFETY: head is a node owned by this list
+                Link::More(node) => Some(unsafe { &mut *node.as_ptr() }),
+            },
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // NOTE: take() is needed here as &mut isn't Copy like & is for Iter
+        self.next.take().map(|node| {
+            self.next = match node.next {
+                Link::Empty => None,
+                // SAFETY: every node linked from the list is owned by it
+                Link::More(node) => Some(unsafe { &mut *node.as_ptr() }),
+            };
+            &mut node.elem
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+// NOTE: encoded format is
+//  - header: MAGIC followed by FORMAT_VERSION byte
+//  - number of elements as LEB128 varint
+//  - every element from head to tail as difference from the previous one (first one from 0),
+//    zig-zag encoded (so that small negative differences are small too) and written as LEB128
+//    varint
+// Lists of nearby values end up taking a byte or two per element
+const MAGIC: &[u8; 4] = b"FLST";
+const FORMAT_VERSION: u8 = 1;
+
+#[derive(Debug)]
+pub enum DecodeError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u8),
+    /// varint is longer than what fits in u64
+    VarintOverflow,
+    /// decoded element (or length) doesn't fit in its type
+    OutOfRange,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Io(err) => write!(f, "io error: {}", err),
+            DecodeError::BadMagic => write!(f, "not an encoded list"),
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            },
+            DecodeError::VarintOverflow => write!(f, "varint overflows u64"),
+            DecodeError::OutOfRange => write!(f, "decoded value out of range"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecodeError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(err: io::Error) -> Self {
+        DecodeError::Io(err)
+    }
+}
+
+impl List<i32> {
+    pub fn encode<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[FORMAT_VERSION])?;
+        write_varint(writer, self.iter().count() as u64)?;
+        let mut prev = 0i64;
+        for &elem in self {
+            let delta = i64::from(elem) - prev;
+            // zig-zag: 0, -1, 1, -2, 2, ... becomes 0, 1, 2, 3, 4, ...
+            write_varint(writer, ((delta << 1) ^ (delta >> 63)) as u64)?;
+            prev = i64::from(elem);
+        }
+        Ok(())
+    }
+
+    pub fn decode<R: Read>(reader: &mut R) -> Result<List<i32>, DecodeError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        let mut version = [0];
+        reader.read_exact(&mut version)?;
+        if version[0] != FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version[0]));
+        }
+
+        let len = read_varint(reader)?;
+        let mut list = List::new();
+        let mut prev = 0i64;
+        for _ in 0..len {
+            let zigzag = read_varint(reader)?;
+            let delta = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+            let elem = prev.checked_add(delta).ok_or(DecodeError::OutOfRange)?;
+            // NOTE: push_back() keeps the original order, and it's O(1) because of the tail
+            list.push_back(i32::try_from(elem).map_err(|_| DecodeError::OutOfRange)?);
+            prev = elem;
+        }
+        Ok(list)
+    }
+}
+
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> io::Result<()> {
+    let mut buf = [0; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&buf[..len])
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> Result<u64, DecodeError> {
+    let mut value = 0u64;
+    let mut byte = [0];
+    for shift in (0..64).step_by(7) {
+        reader.read_exact(&mut byte)?;
+        let bits = u64::from(byte[0] & 0x7f);
+        // last (10th) byte can only carry the single remaining bit
+        if shift == 63 && bits > 1 {
+            return Err(DecodeError::VarintOverflow);
+        }
+        value |= bits << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(DecodeError::VarintOverflow)
+}
+
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        // NOTE: freeing nodes one by one from the head, so it doesn't recurse down the chain
+        while self.pop().is_some() {}
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::List;
+    use std::ptr;
+
+    use super::{DecodeError, Link, List};
+
+    /// Walks the list and checks that the cached tail is the last node (or None when empty)
+    fn assert_tail<T>(list: &List<T>) {
+        let mut last: *const super::Node<T> = ptr::null();
+        let mut current = list.head;
+        while let Link::More(node) = current {
+            last = node.as_ptr();
+            // SAFETY: node is owned by the list, which is borrowed for the whole walk
+            current = unsafe { node.as_ref() }.next;
+        }
+        assert_eq!(last, list.tail.map_or(ptr::null(), |tail| tail.as_ptr() as *const _));
+    }
+
+    // NOTE: doesn't compile if List stops being covariant in T (e.g. with *mut tail)
+    #[allow(dead_code)]
+    fn covariant<'a>(list: List<&'static str>) -> List<&'a str> {
+        list
+    }
+
+    // NOTE: tests with long lists run short ones under Miri, which is far too slow for the rest
+    const LONG: u32 = if cfg!(miri) { 100 } else { 100_000 };
+
+    fn collect(list: List<i32>) -> Vec<i32> {
+        list.into_iter().collect()
+    }
 
 
     #[test]
@@ -258,4 +859,562 @@ mod test {
         assert_eq!(list2.pop(), Some(1));
         assert_eq!(list2.pop(), None);
     }
+
+    #[test]
+    fn test_non_copy_elem() {
+        let mut list = List::new();
+        list.push("one".to_string());
+        list.push("two".to_string());
+        list.push("three".to_string());
+
+        let mut rest = list.split_next("two".to_string()).unwrap();
+        assert_eq!(rest.pop(), Some("one".to_string()));
+        assert_eq!(rest.pop(), None);
+        assert_eq!(list.pop(), Some("three".to_string()));
+        assert_eq!(list.pop(), Some("two".to_string()));
+        assert_eq!(list.pop(), None);
+
+        // iterative drop shouldn't blow the stack for heap owning payloads either
+        let mut list = List::new();
+        for i in 0..LONG {
+            list.push(i.to_le_bytes().to_vec());
+        }
+        drop(list);
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut list = List::new();
+        assert_eq!(list.peek(), None);
+        assert_eq!(list.peek_mut(), None);
+        list.push(10);
+        list.push(20);
+        assert_eq!(list.peek(), Some(&20));
+        if let Some(elem) = list.peek_mut() {
+            *elem = 30;
+        }
+        assert_eq!(list.pop(), Some(30));
+        assert_eq!(list.peek_mut(), Some(&mut 10));
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut list = List::new();
+        list.push(10);
+        list.push(20);
+        list.push(30);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next(), Some(30));
+        assert_eq!(iter.next(), Some(20));
+        assert_eq!(iter.next(), Some(10));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut list = List::new();
+        list.push(10);
+        list.push(20);
+        list.push(30);
+
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(&30));
+        assert_eq!(iter.next(), Some(&20));
+        assert_eq!(iter.next(), Some(&10));
+        assert_eq!(iter.next(), None);
+
+        // list is still usable after borrowing iteration
+        let mut sum = 0;
+        for elem in &list {
+            sum += elem;
+        }
+        assert_eq!(sum, 60);
+        assert_eq!(list.pop(), Some(30));
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut list = List::new();
+        list.push(10);
+        list.push(20);
+        list.push(30);
+
+        let mut iter = list.iter_mut();
+        assert_eq!(iter.next(), Some(&mut 30));
+        assert_eq!(iter.next(), Some(&mut 20));
+        assert_eq!(iter.next(), Some(&mut 10));
+        assert_eq!(iter.next(), None);
+
+        for elem in &mut list {
+            *elem += 1;
+        }
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![31, 21, 11]);
+    }
+
+    #[test]
+    fn test_split_when() {
+        let mut list = List::new();
+        list.push(-2);
+        list.push(-1);
+        list.push(1);
+        list.push(2);
+
+        assert!(list.split_when(|elem| *elem > 10).is_none());
+
+        // first negative value
+        let mut negatives = list.split_when(|elem| *elem < 0).unwrap();
+        assert_eq!(negatives.pop(), Some(-1));
+        assert_eq!(negatives.pop(), Some(-2));
+        assert_eq!(negatives.pop(), None);
+
+        // splitting at head moves everything
+        let mut all = list.split_when(|_| true).unwrap();
+        assert_eq!(list.pop(), None);
+        assert_eq!(all.pop(), Some(2));
+        assert_eq!(all.pop(), Some(1));
+        assert_eq!(all.pop(), None);
+    }
+
+    #[test]
+    fn test_split_after_when() {
+        let mut list = List::new();
+        list.push(3);
+        list.push(2);
+        list.push(1);
+
+        assert!(list.split_after_when(|elem| *elem == 10).is_none());
+
+        // matching the last node gives empty list
+        let mut empty = list.split_after_when(|elem| *elem == 3).unwrap();
+        assert_eq!(empty.pop(), None);
+
+        let mut calls = 0;
+        let mut rest = list
+            .split_after_when(|elem| {
+                calls += 1;
+                *elem % 2 == 1
+            })
+            .unwrap();
+        assert_eq!(calls, 1);
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.pop(), None);
+        assert_eq!(rest.pop(), Some(2));
+        assert_eq!(rest.pop(), Some(3));
+        assert_eq!(rest.pop(), None);
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut list = List::new();
+        for elem in (0..5).rev() {
+            list.push(elem);
+        }
+
+        assert!(list.split_off(6).is_none());
+
+        // split at length gives empty list
+        let mut empty = list.split_off(5).unwrap();
+        assert_eq!(empty.pop(), None);
+
+        let rest = list.split_off(2).unwrap();
+        assert_eq!(rest.into_iter().collect::<Vec<_>>(), vec![2, 3, 4]);
+
+        let all = list.split_off(0).unwrap();
+        assert_eq!(list.pop(), None);
+        assert_eq!(all.into_iter().collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_split_every() {
+        assert!(List::<i32>::new().split_every(3).is_empty());
+
+        let mut list = List::new();
+        for elem in (0..7).rev() {
+            list.push(elem);
+        }
+        let chunks = list
+            .split_every(3)
+            .into_iter()
+            .map(|chunk| chunk.into_iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(chunks, vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+
+        let mut list = List::new();
+        for elem in (0..4).rev() {
+            list.push(elem);
+        }
+        let chunks = list
+            .split_every(2)
+            .into_iter()
+            .map(|chunk| chunk.into_iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(chunks, vec![vec![0, 1], vec![2, 3]]);
+    }
+
+    #[test]
+    fn test_push_back() {
+        let mut list = List::new();
+        assert_tail(&list);
+        list.push_back(1);
+        assert_tail(&list);
+        list.push_back(2);
+        list.push(0);
+        list.push_back(3);
+        assert_tail(&list);
+
+        assert_eq!(list.pop(), Some(0));
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.pop(), Some(2));
+        assert_tail(&list);
+        assert_eq!(list.pop(), Some(3));
+        assert_tail(&list);
+
+        // list emptied with pop should start again from null tail
+        list.push_back(4);
+        list.push(5);
+        assert_tail(&list);
+        assert_eq!(collect(list), vec![5, 4]);
+    }
+
+    #[test]
+    fn test_split_next_tail() {
+        let mut list = List::new();
+        for elem in 1..=5 {
+            list.push(elem);
+        }
+
+        assert!(list.split_next(10).is_none());
+        assert_tail(&list);
+
+        // split after last node
+        let mut list2 = list.split_next(1).unwrap();
+        assert_tail(&list);
+        assert_tail(&list2);
+        list2.push_back(6);
+        assert_eq!(collect(list2), vec![6]);
+
+        // split after first node
+        let mut moved_list = list.split_next(5).unwrap();
+        assert_tail(&list);
+        assert_tail(&moved_list);
+        list.push_back(0);
+        assert_eq!(collect(list), vec![5, 0]);
+
+        // split from between
+        let mut half_list = moved_list.split_next(3).unwrap();
+        assert_tail(&moved_list);
+        assert_tail(&half_list);
+        moved_list.push_back(0);
+        half_list.push_back(0);
+        assert_eq!(collect(moved_list), vec![4, 3, 0]);
+        assert_eq!(collect(half_list), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_split_at_tail() {
+        let mut list = List::new();
+        for elem in 1..=5 {
+            list.push(elem);
+        }
+
+        assert!(list.split_at(10).is_none());
+        assert_tail(&list);
+
+        // split at last node
+        let mut list2 = list.split_at(1).unwrap();
+        assert_tail(&list);
+        assert_tail(&list2);
+        list2.push_back(6);
+        assert_eq!(collect(list2), vec![1, 6]);
+
+        // split at first node, old list is left empty
+        let mut moved_list = list.split_at(5).unwrap();
+        assert_tail(&list);
+        assert_tail(&moved_list);
+        list.push_back(0);
+        assert_eq!(collect(list), vec![0]);
+
+        // split from between
+        let mut half_list = moved_list.split_at(3).unwrap();
+        assert_tail(&moved_list);
+        assert_tail(&half_list);
+        moved_list.push_back(0);
+        half_list.push_back(0);
+        assert_eq!(collect(moved_list), vec![5, 4, 0]);
+        assert_eq!(collect(half_list), vec![3, 2, 0]);
+    }
+
+    #[test]
+    fn test_split_off_tail() {
+        let mut list = List::new();
+        for elem in 0..4 {
+            list.push_back(elem);
+        }
+        let mut rest = list.split_off(4).unwrap();
+        assert_tail(&list);
+        assert_tail(&rest);
+
+        rest = list.split_off(1).unwrap();
+        assert_tail(&list);
+        assert_tail(&rest);
+
+        for mut chunk in rest.split_every(2) {
+            assert_tail(&chunk);
+            chunk.push_back(9);
+            assert_tail(&chunk);
+        }
+    }
+
+    #[test]
+    fn test_merge_tail() {
+        let mut list1 = List::new();
+
+        // merging empty lists keeps null tail
+        list1.merge(List::new());
+        assert_tail(&list1);
+
+        let mut list2 = List::new();
+        list2.push_back(1);
+        list2.push_back(2);
+        list1.merge(list2);
+        assert_tail(&list1);
+
+        list1.merge(List::new());
+        assert_tail(&list1);
+
+        // merge is O(1) now, merging many small lists shouldn't walk the whole list
+        for elem in 3..10_000 {
+            let mut list = List::new();
+            list.push(elem);
+            list1.merge(list);
+        }
+        assert_tail(&list1);
+        list1.push_back(10_000);
+        assert_eq!(collect(list1), (1..=10_000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut list = List::<i32>::new();
+        list.sort();
+        assert_tail(&list);
+        assert_eq!(list.pop(), None);
+
+        for elem in [5, 3, 9, 1, 3, 7, 0, 2] {
+            list.push_back(elem);
+        }
+        list.sort();
+        assert_tail(&list);
+        list.push_back(10);
+        assert_eq!(collect(list), vec![0, 1, 2, 3, 3, 5, 7, 9, 10]);
+
+        let mut list = List::new();
+        for elem in [1, 2, 3] {
+            list.push(elem);
+        }
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(collect(list), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_sort_stable() {
+        let mut list = List::new();
+        for (i, key) in [3, 1, 2, 1, 3, 2, 1].into_iter().enumerate() {
+            list.push_back((key, i));
+        }
+        list.sort_by_key(|&(key, _)| key);
+        assert_eq!(
+            list.into_iter().collect::<Vec<_>>(),
+            vec![(1, 1), (1, 3), (1, 6), (2, 2), (2, 5), (3, 0), (3, 4)]
+        );
+    }
+
+    #[test]
+    fn test_sort_relinks_nodes() {
+        let mut list = List::new();
+        for elem in 0..LONG * 10 {
+            // scramble the order a bit
+            list.push(elem.wrapping_mul(2_654_435_761) % 1_000_003);
+        }
+        let mut before = list.iter().map(|elem| elem as *const u32).collect::<Vec<_>>();
+        list.sort();
+        assert_tail(&list);
+
+        let mut after = list.iter().map(|elem| elem as *const u32).collect::<Vec<_>>();
+        assert!(list.iter().zip(list.iter().skip(1)).all(|(a, b)| a <= b));
+
+        // same nodes, just in different order
+        before.sort();
+        after.sort();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut list = List::<i32>::new();
+        list.reverse();
+        assert_tail(&list);
+        assert_eq!(list.pop(), None);
+
+        list.push(1);
+        list.reverse();
+        assert_tail(&list);
+
+        list.push(2);
+        list.push(3);
+        let before = list.iter().map(|elem| elem as *const i32).collect::<Vec<_>>();
+        list.reverse();
+        assert_tail(&list);
+        // same nodes in reverse order
+        let mut after = list.iter().map(|elem| elem as *const i32).collect::<Vec<_>>();
+        after.reverse();
+        assert_eq!(before, after);
+
+        list.push_back(4);
+        assert_eq!(collect(list), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let make = || {
+            let mut list = List::new();
+            for elem in 0..5 {
+                list.push_back(elem);
+            }
+            list
+        };
+
+        let mut list = List::<i32>::new();
+        list.rotate_left(3);
+        list.rotate_right(3);
+        assert_eq!(list.pop(), None);
+
+        let mut list = make();
+        list.rotate_left(2);
+        assert_tail(&list);
+        assert_eq!(collect(list), vec![2, 3, 4, 0, 1]);
+
+        let mut list = make();
+        list.rotate_left(5);
+        assert_tail(&list);
+        assert_eq!(collect(list), vec![0, 1, 2, 3, 4]);
+
+        let mut list = make();
+        list.rotate_left(7);
+        assert_eq!(collect(list), vec![2, 3, 4, 0, 1]);
+
+        let mut list = make();
+        list.rotate_right(1);
+        assert_tail(&list);
+        list.push_back(5);
+        assert_eq!(collect(list), vec![4, 0, 1, 2, 3, 5]);
+
+        let mut list = make();
+        list.rotate_right(10);
+        assert_eq!(collect(list), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_reverse_groups() {
+        let make = |len| {
+            let mut list = List::new();
+            for elem in 0..len {
+                list.push_back(elem);
+            }
+            list
+        };
+
+        let mut list = make(0);
+        list.reverse_groups(2);
+        assert_tail(&list);
+        assert_eq!(list.pop(), None);
+
+        let mut list = make(7);
+        list.reverse_groups(3);
+        assert_tail(&list);
+        assert_eq!(collect(list), vec![2, 1, 0, 5, 4, 3, 6]);
+
+        let mut list = make(6);
+        list.reverse_groups(2);
+        assert_tail(&list);
+        list.push_back(6);
+        assert_eq!(collect(list), vec![1, 0, 3, 2, 5, 4, 6]);
+
+        // group larger than the list leaves it untouched
+        let mut list = make(3);
+        list.reverse_groups(4);
+        assert_eq!(collect(list), vec![0, 1, 2]);
+
+        let mut list = make(3);
+        list.reverse_groups(1);
+        assert_eq!(collect(list), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let round_trip = |list: &List<i32>| {
+            let mut buf = Vec::new();
+            list.encode(&mut buf).unwrap();
+            let decoded = List::decode(&mut buf.as_slice()).unwrap();
+            assert_tail(&decoded);
+            (buf.len(), decoded)
+        };
+
+        let (len, decoded) = round_trip(&List::new());
+        // header and zero length
+        assert_eq!(len, 6);
+        assert_eq!(collect(decoded), vec![]);
+
+        let mut list = List::new();
+        for elem in [i32::MIN, 0, -1, i32::MAX, 7, i32::MIN, i32::MAX] {
+            list.push_back(elem);
+        }
+        let (_, decoded) = round_trip(&list);
+        assert_eq!(collect(decoded), collect(list));
+
+        // nearby values take one byte each
+        let mut list = List::new();
+        for elem in (-1000..1000).rev() {
+            list.push(elem);
+        }
+        let (len, decoded) = round_trip(&list);
+        assert!(len < 2000 + 10);
+        assert_eq!(collect(decoded), (-1000..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut list = List::new();
+        list.push(300);
+        list.push(-5);
+        let mut buf = Vec::new();
+        list.encode(&mut buf).unwrap();
+
+        let mut bad = buf.clone();
+        bad[0] = b'X';
+        assert!(matches!(List::decode(&mut bad.as_slice()), Err(DecodeError::BadMagic)));
+
+        let mut bad = buf.clone();
+        bad[4] = 2;
+        assert!(matches!(
+            List::decode(&mut bad.as_slice()),
+            Err(DecodeError::UnsupportedVersion(2))
+        ));
+
+        // truncated input
+        let bad = &buf[..buf.len() - 1];
+        assert!(matches!(List::decode(&mut &bad[..]), Err(DecodeError::Io(_))));
+
+        // value out of i32 range (delta of i32::MAX + 1 from 0)
+        let mut bad = buf[..5].to_vec();
+        bad.extend_from_slice(&[1, 0x80, 0x80, 0x80, 0x80, 0x10]);
+        assert!(matches!(List::decode(&mut bad.as_slice()), Err(DecodeError::OutOfRange)));
+
+        // varint longer than u64
+        let mut bad = buf[..5].to_vec();
+        bad.extend_from_slice(&[0xff; 10]);
+        bad.push(1);
+        assert!(matches!(List::decode(&mut bad.as_slice()), Err(DecodeError::VarintOverflow)));
+    }
 }
@@ -2,6 +2,9 @@ pub mod first;
 pub mod second;
 pub mod third;
 pub mod fourth;
+pub mod stack_vm;
+pub mod ffi;
+pub mod big_uint;
 
 #[allow(dead_code)]
 fn tryout() { }
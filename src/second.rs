@@ -1,7 +1,20 @@
-use std::fmt::Display;
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    mem::{self, MaybeUninit},
+    ptr,
+};
+
+mod algorithms;
 
 pub struct List<T> {
     head: Link<T>,
+    // NOTE: every function which adds, removes or moves nodes has to keep this right
+    len: usize,
+    // NOTE: boxed so that lists without a pool only pay for a null pointer
+    pool: Option<Box<NodePool<T>>>,
 }
 
 pub struct Node<T> {
@@ -11,23 +24,98 @@ pub struct Node<T> {
 
 pub type Link<T> = Option<Box<Node<T>>>;
 
+/// Allocations of popped nodes, kept for later pushes to reuse instead of calling the allocator
+struct NodePool<T> {
+    // NOTE: element of every node here is already moved out (or dropped), so these are just
+    // allocations of the right size and alignment. Dropping the vec frees them without touching
+    // the contents
+    slots: Vec<Box<MaybeUninit<Node<T>>>>,
+    max: usize,
+}
+
+impl<T> NodePool<T> {
+    fn new(max: usize) -> Self {
+        Self {
+            slots: Vec::new(),
+            max,
+        }
+    }
+
+    fn alloc(&mut self, node: Node<T>) -> Box<Node<T>> {
+        match self.slots.pop() {
+            Some(mut slot) => {
+                slot.write(node);
+                // SAFETY: slot has just been initialized, and MaybeUninit<Node<T>> has the same
+                // layout as Node<T>
+                unsafe { Box::from_raw(Box::into_raw(slot).cast::<Node<T>>()) }
+            },
+            None => Box::new(node),
+        }
+    }
+
+    /// Moves the element out of the node, keeping the node's allocation if there's room for it
+    fn recycle(&mut self, node: Box<Node<T>>) -> T {
+        if self.slots.len() >= self.max {
+            return node.elem;
+        }
+        debug_assert!(node.next.is_none(), "recycled node still links to the rest of list");
+        let node = Box::into_raw(node);
+        // SAFETY: node is valid and is only treated as uninitialized after elem is read out of it.
+        // next is None so there's nothing else left to drop
+        unsafe {
+            let elem = ptr::read(ptr::addr_of!((*node).elem));
+            self.slots
+                .push(Box::from_raw(node.cast::<MaybeUninit<Node<T>>>()));
+            elem
+        }
+    }
+}
+
 impl<T> Display for Node<T>
 where
     T: Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", self.elem)
+        write!(f, "{}", self.elem)
     }
 }
 
-// NOTE: trait restriction is needed because our split from matches the element with equality
-// operator to break the list
-impl<T> List<T>
-where
-    T: PartialEq,
-{
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> List<T> {
     pub fn new() -> Self {
-        Self { head: None }
+        Self {
+            head: None,
+            len: 0,
+            pool: None,
+        }
+    }
+
+    /// Returns an empty list which keeps up to `max_pooled` popped nodes around, so that later
+    /// pushes can reuse them instead of allocating. Only push and pop go through the pool, lists
+    /// split off this one don't get a pool of their own
+    pub fn with_node_pool(max_pooled: usize) -> Self {
+        Self {
+            head: None,
+            len: 0,
+            pool: Some(Box::new(NodePool::new(max_pooled))),
+        }
+    }
+
+    /// Number of nodes waiting in the pool to be reused
+    pub fn pooled_nodes(&self) -> usize {
+        self.pool.as_ref().map_or(0, |pool| pool.slots.len())
+    }
+
+    /// Frees all the nodes kept in the pool, pool itself stays enabled
+    pub fn shrink_pool(&mut self) {
+        if let Some(pool) = self.pool.as_mut() {
+            pool.slots = Vec::new();
+        }
     }
 
     pub fn push(&mut self, elem: T) {
@@ -37,16 +125,32 @@ where
             elem,
             next: self.head.take(),
         };
-        self.head = Some(Box::new(node));
+        self.head = Some(match self.pool.as_mut() {
+            Some(pool) => pool.alloc(node),
+            None => Box::new(node),
+        });
+        self.len += 1;
     }
 
     pub fn pop(&mut self) -> Option<T> {
-        self.head.take().map(|node| {
-            self.head = node.next;
-            node.elem
+        self.head.take().map(|mut node| {
+            self.head = node.next.take();
+            self.len -= 1;
+            match self.pool.as_mut() {
+                Some(pool) => pool.recycle(node),
+                None => node.elem,
+            }
         })
     }
 
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     pub fn peek(&self) -> Option<&T> {
         self.head.as_ref().map(|node| &node.elem)
         // NOTE: If above is unclear, just do below
@@ -60,19 +164,33 @@ where
     pub fn peek_mut(&mut self) -> Option<&mut T> {
         self.head.as_mut().map(|node| &mut node.elem)
     }
+}
 
+// NOTE: trait restriction is needed because our split from matches the element with equality
+// operator to break the list. Rest of the list works for any T (see split_when() and
+// split_after_when() for the splits which don't need it)
+impl<T> List<T>
+where
+    T: PartialEq,
+{
     /// Split on the basis of element match
     /// Returns the new list from the next node of the node which matched the elem provided as
     /// argument
     pub fn split_next(&mut self, elem: T) -> Option<List<T>> {
+        // NOTE: index of the current node, which is also the number of nodes before it. Nodes
+        // are counted on the way, the detached part gets the rest of len
+        let mut index = 0;
         let mut current = &mut self.head;
         while let Some(ref mut node) = current {
             if node.elem == elem {
                 let mut list = List::new();
                 list.head = node.next.take();
+                list.len = self.len - index - 1;
+                self.len = index + 1;
                 return Some(list);
             }
             current = &mut node.next;
+            index += 1;
         }
         None
     }
@@ -84,6 +202,8 @@ where
             if node.elem == elem {
                 let mut list = List::new();
                 list.head = self.head.take();
+                list.len = self.len;
+                self.len = 0;
                 return Some(list);
             } else {
                 let mut node_next = &mut node.next;
@@ -105,15 +225,21 @@ where
     }
 
     pub fn split_at(&mut self, elem: T) -> Option<List<T>> {
+        let mut index = 0;
         let mut node_next = &mut self.head;
         loop {
             match node_next {
                 Some(node) if node.elem == elem => {
                     let mut list = List::new();
                     list.head = node_next.take();
+                    list.len = self.len - index;
+                    self.len = index;
                     break Some(list);
                 }
-                Some(node) => node_next = &mut node.next,
+                Some(node) => {
+                    node_next = &mut node.next;
+                    index += 1;
+                }
                 None => break None,
             };
         } // <- this is expression
@@ -121,11 +247,137 @@ where
           // this method
     }
 
+    /// Returns the index at which `pattern` first shows up as a run of consecutive elements
+    /// Linear in the length of list plus pattern (KMP), empty pattern is found at 0
+    pub fn find_sublist(&self, pattern: &[T]) -> Option<usize> {
+        if pattern.is_empty() {
+            return Some(0);
+        }
+        let table = kmp_table(pattern);
+        let mut matched = 0;
+        for (index, elem) in self.iter().enumerate() {
+            matched = kmp_step(pattern, &table, matched, elem);
+            if matched == pattern.len() {
+                return Some(index + 1 - matched);
+            }
+        }
+        None
+    }
+
+    pub fn contains_sublist(&self, pattern: &[T]) -> bool {
+        self.find_sublist(pattern).is_some()
+    }
+
+    /// Splits the list into the pieces between (non overlapping) occurrences of `pattern`, like
+    /// str::split(). Nodes of the pieces are the list's own, only the ones matching the pattern
+    /// are dropped. Empty pattern doesn't split anything
+    pub fn split_on_sequence(mut self, pattern: &[T]) -> Vec<List<T>> {
+        if pattern.is_empty() {
+            return vec![self];
+        }
+        let table = kmp_table(pattern);
+        let mut pieces = Vec::new();
+        // NOTE: piece is built in reverse by pushing the nodes onto it, so that the nodes of the
+        // match just completed are right at its head
+        let mut reversed = List::new();
+        let mut matched = 0;
+        while let Some(node) = self.pop_node() {
+            matched = kmp_step(pattern, &table, matched, &node.elem);
+            reversed.push_node(node);
+            if matched == pattern.len() {
+                for _ in 0..matched {
+                    reversed.pop_node();
+                }
+                pieces.push(reversed.reversed());
+                reversed = List::new();
+                matched = 0;
+            }
+        }
+        pieces.push(reversed.reversed());
+        pieces
+    }
+}
+
+/// KMP failure function, `table[i]` is the length of the longest proper prefix of
+/// `pattern[..=i]` which is also its suffix
+fn kmp_table<T: PartialEq>(pattern: &[T]) -> Vec<usize> {
+    let mut table = vec![0; pattern.len()];
+    let mut matched = 0;
+    for i in 1..pattern.len() {
+        matched = kmp_step(pattern, &table[..i], matched, &pattern[i]);
+        table[i] = matched;
+    }
+    table
+}
+
+/// Returns how much of the pattern is matched after `elem`, given `matched` elements of it were
+/// matched before (which has to be less than the whole pattern)
+fn kmp_step<T: PartialEq>(pattern: &[T], table: &[usize], mut matched: usize, elem: &T) -> usize {
+    while matched > 0 && pattern[matched] != *elem {
+        matched = table[matched - 1];
+    }
+    if pattern[matched] == *elem {
+        matched += 1;
+    }
+    matched
+}
+
+impl<T> List<T> {
+    /// Split on the basis of predicate
+    /// Returns the new list starting from the first node for which the predicate returned true
+    pub fn split_when<F>(&mut self, mut pred: F) -> Option<List<T>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut index = 0;
+        let mut node_next = &mut self.head;
+        loop {
+            match node_next {
+                Some(node) if pred(&node.elem) => {
+                    let mut list = List::new();
+                    list.head = node_next.take();
+                    list.len = self.len - index;
+                    self.len = index;
+                    break Some(list);
+                }
+                Some(node) => {
+                    node_next = &mut node.next;
+                    index += 1;
+                }
+                None => break None,
+            };
+        }
+    }
+
+    /// Split on the basis of predicate
+    /// Returns the new list from the next node of the first node for which the predicate returned
+    /// true
+    pub fn split_after_when<F>(&mut self, mut pred: F) -> Option<List<T>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut index = 0;
+        let mut current = &mut self.head;
+        while let Some(ref mut node) = current {
+            if pred(&node.elem) {
+                let mut list = List::new();
+                list.head = node.next.take();
+                list.len = self.len - index - 1;
+                self.len = index + 1;
+                return Some(list);
+            }
+            current = &mut node.next;
+            index += 1;
+        }
+        None
+    }
+
     pub fn merge(&mut self, mut list: List<T>) {
+        self.len += list.len;
+        list.len = 0;
         match &mut self.head {
             None => {
                 self.head = list.head.take();
-                return;
             },
             Some(_) => {
                 let mut next_node = &mut self.head;
@@ -143,33 +395,56 @@ where
 
 pub struct IntoIter<T>(List<T>);
 
-impl<T> List<T> {
-    pub fn into_iter(self) -> IntoIter<T> {
-        IntoIter(self)
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
     }
 }
 
-// NOTE: We need to satisfy the trait bound for IntoIterator too because List<T> has the constraint
-// This constraint can be more tight, but can't be more loose.
-impl<T> Iterator for IntoIter<T>
-where
-    T: PartialEq,
-{
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+// NOTE: List used to implement Iterator directly (popping from the head) besides having an
+// inherent into_iter(). That made list.into_iter(), list.cmp(..) etc. depend on method resolution,
+// and it also looked like the list could be used again after iterating over it. Now consuming
+// iteration only goes through IntoIter, which takes the ownership of the list
+impl<T> IntoIterator for List<T> {
     type Item = T;
+    type IntoIter = IntoIter<T>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.pop()
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
     }
 }
 
+// NOTE: borrowing iterators carry the number of elements left, as they can't get to the list's len
 pub struct Iter<'a, T> {
     next: Option<&'a Node<T>>,
+    len: usize,
 }
 
 impl<T> List<T> {
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             next: self.head.as_deref(),
+            len: self.len,
+        }
+    }
+}
+
+// NOTE: derive would need T: Clone, but only the reference is cloned here
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            next: self.next,
+            len: self.len,
         }
     }
 }
@@ -188,80 +463,1210 @@ impl<'a, T> Iterator for Iter<'a, T> {
             // self.next = node.next.as_deref();
             // or
             self.next = node.next.as_ref().map::<&Node<T>, _>(|node| node);
+            self.len -= 1;
             &node.elem
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
 pub struct IterMut<'a, T> {
     next: Option<&'a mut Node<T>>,
+    len: usize,
 }
 
 impl<T> List<T> {
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
             next: self.head.as_deref_mut(),
             // NOTE: above is short (or better syntactically) for
             // next: self.head.as_mut().map(|node| {
             //     &mut **node
             // })
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.take().map(|node| {
+            // self.next = node.next.as_mut().map(|node| node.as_mut());
+            // self.next = node.next.as_mut().map(|node| &mut **node);
+            //
+            // NOTE: both the above lines are correct, here as_deref() is deref coercion for
+            // &**node
+            //
+            // self.next = node.next.as_deref_mut();
+            // or
+            self.next = node.next.as_mut().map::<&mut Node<T>, _>(|node| node);
+            self.len -= 1;
+            &mut node.elem
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a mut List<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Cursor which walks the list forward and edits it around its position, without re-scanning
+/// from the head for every edit
+///
+/// It starts before the head (where there's no current element), so that insert_after(),
+/// splice_after() etc. can work on the front of the list too
+pub struct CursorMut<'a, T> {
+    // NOTE: current element and the link after it are disjoint fields of the same node, so both
+    // can be borrowed mutably at the same time. Before the head, link after is the head itself
+    current: Option<&'a mut T>,
+    // NOTE: this is always Some, Option is only there to move the reference out while moving
+    // forward, as reborrowing it through &mut self wouldn't live long enough
+    next: Option<&'a mut Link<T>>,
+    index: Option<usize>,
+    list_len: &'a mut usize,
+}
+
+impl<T> List<T> {
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            current: None,
+            next: Some(&mut self.head),
+            index: None,
+            list_len: &mut self.len,
+        }
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    fn next_link(&mut self) -> &mut Link<T> {
+        self.next.as_mut().expect("cursor always has the next link")
+    }
+
+    /// Index of the current element, None if cursor is before the head
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current.as_deref_mut()
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        self.next_link().as_mut().map(|node| &mut node.elem)
+    }
+
+    /// Moves to the next element, returns false (and stays) if current is the last one
+    pub fn move_next(&mut self) -> bool {
+        let next = self.next.take().expect("cursor always has the next link");
+        if next.is_none() {
+            self.next = Some(next);
+            return false;
+        }
+        // NOTE: checking is_none() first and unwrapping after is needed, matching on next would
+        // borrow it for 'a in the None arm too (same borrow checker limitation as in
+        // early_split_at())
+        let node = Option::as_mut(next).unwrap();
+        self.current = Some(&mut node.elem);
+        self.next = Some(&mut node.next);
+        self.index = Some(self.index.map_or(0, |index| index + 1));
+        true
+    }
+
+    /// Inserts the element after current one (at head if cursor is before the head), cursor
+    /// doesn't move
+    pub fn insert_after(&mut self, elem: T) {
+        let link = self.next_link();
+        *link = Some(Box::new(Node {
+            elem,
+            next: link.take(),
+        }));
+        *self.list_len += 1;
+    }
+
+    /// Removes the element after current one (head if cursor is before the head)
+    pub fn remove_next(&mut self) -> Option<T> {
+        let link = self.next_link();
+        let elem = link.take().map(|node| {
+            *link = node.next;
+            node.elem
+        });
+        if elem.is_some() {
+            *self.list_len -= 1;
+        }
+        elem
+    }
+
+    /// Returns the list of all the elements after current one, which are removed from this list
+    pub fn split_after(&mut self) -> List<T> {
+        let kept = self.index.map_or(0, |index| index + 1);
+        let list = List {
+            head: self.next_link().take(),
+            len: *self.list_len - kept,
+            pool: None,
+        };
+        *self.list_len = kept;
+        list
+    }
+
+    /// Inserts all the elements of `list` after current one, keeping their order
+    pub fn splice_after(&mut self, mut list: List<T>) {
+        let link = self.next_link();
+        *list.last_link() = link.take();
+        *link = list.head.take();
+        *self.list_len += list.len;
+        list.len = 0;
+    }
+}
+
+impl<T> List<T> {
+    /// Keeps only the elements for which `f` returns true, rest of the nodes are unlinked and
+    /// dropped where they are (order of the kept ones doesn't change)
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|elem| f(elem));
+    }
+
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let mut link = &mut self.head;
+        // NOTE: f looks at the node while it's still linked, only rejected nodes are taken out.
+        // If f panics, the list is left whole (and len right) with the nodes it didn't get to
+        while let Some(node) = link.as_mut() {
+            if f(&mut node.elem) {
+                link = &mut link.as_mut().expect("checked above").next;
+            } else if let Some(mut rejected) = link.take() {
+                *link = rejected.next.take();
+                self.len -= 1;
+                // rejected node gets dropped here, next was taken out so drop stops at it
+            }
+        }
+    }
+
+    /// Returns the iterator which unlinks and yields the elements for which `pred` returns true
+    ///
+    /// It's lazy, nothing is removed till the iterator gets there. Elements it didn't get to (if
+    /// it's dropped early) stay in the list
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        ExtractIf {
+            link: Some(&mut self.head),
+            len: &mut self.len,
+            pred,
+        }
+    }
+
+    /// Returns the iterator which pops all the elements, list is empty once the iterator is
+    /// dropped (even if it wasn't exhausted)
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain { list: self }
+    }
+}
+
+// NOTE: positional API, index 0 being the head. As len is known, out of range indices are caught
+// before walking the list, instead of panicking (or walking it for nothing)
+impl<T> List<T> {
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        self.iter().nth(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        self.iter_mut().nth(index)
+    }
+
+    /// Inserts the element at `index`, shifting the rest after it
+    /// Returns the element back if `index` is greater than len
+    pub fn insert_at(&mut self, index: usize, elem: T) -> Result<(), T> {
+        if index > self.len {
+            return Err(elem);
+        }
+        self.len += 1;
+        let link = self.link_at(index);
+        *link = Some(Box::new(Node {
+            elem,
+            next: link.take(),
+        }));
+        Ok(())
+    }
+
+    pub fn remove_at(&mut self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        self.len -= 1;
+        let link = self.link_at(index);
+        link.take().map(|node| {
+            *link = node.next;
+            node.elem
+        })
+    }
+
+    /// Returns the new list starting from the node at `index`, leaving the first `index` nodes
+    /// in this list. Returns None if `index` is greater than len (splitting at len gives empty
+    /// list, like Vec::split_off)
+    pub fn split_off(&mut self, index: usize) -> Option<List<T>> {
+        if index > self.len {
+            return None;
+        }
+        let len = self.len - index;
+        self.len = index;
+        let mut list = List::new();
+        list.head = self.link_at(index).take();
+        list.len = len;
+        Some(list)
+    }
+
+    /// Returns the link holding the node at `index` (the link after the last node when `index`
+    /// is len)
+    // NOTE: breaking out with node_next from a guarded match arm (like split_at() does) doesn't
+    // compile here, as returning it needs the borrow taken by the guarded arm to outlive the
+    // function. Counting nodes with for loop doesn't need a guard
+    fn link_at(&mut self, index: usize) -> &mut Link<T> {
+        let mut node_next = &mut self.head;
+        for _ in 0..index {
+            match node_next {
+                Some(node) => node_next = &mut node.next,
+                None => break,
+            };
         }
+        node_next
+    }
+}
+
+/// Which nodes a linear pass over two sorted lists keeps, see List::combine_sorted()
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SortedOp {
+    Merge,
+    Union,
+    Intersection,
+    Difference,
+    SymmetricDifference,
+}
+
+// NOTE: all of these take two lists sorted by the same order (`compare` for _by versions) and
+// relink their nodes into the result, nodes which don't make it to the result are dropped.
+// Set operations pair up equal elements one to one (like C++'s std::set_union and friends), so
+// with duplicates [1, 1, 2] and [1, 3] have 1 in common once, and element from self is the one
+// kept out of the pair
+impl<T> List<T> {
+    /// Interleaves two sorted lists into one sorted list, elements of self come first among the
+    /// equal ones
+    pub fn merge_sorted(self, other: List<T>) -> List<T>
+    where
+        T: Ord,
+    {
+        self.merge_sorted_by(other, T::cmp)
+    }
+
+    pub fn merge_sorted_by<F>(self, other: List<T>, compare: F) -> List<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.combine_sorted(other, compare, SortedOp::Merge)
+    }
+
+    pub fn union(self, other: List<T>) -> List<T>
+    where
+        T: Ord,
+    {
+        self.union_by(other, T::cmp)
+    }
+
+    pub fn union_by<F>(self, other: List<T>, compare: F) -> List<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.combine_sorted(other, compare, SortedOp::Union)
+    }
+
+    pub fn intersection(self, other: List<T>) -> List<T>
+    where
+        T: Ord,
+    {
+        self.intersection_by(other, T::cmp)
+    }
+
+    pub fn intersection_by<F>(self, other: List<T>, compare: F) -> List<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.combine_sorted(other, compare, SortedOp::Intersection)
+    }
+
+    /// Elements of self which aren't in other
+    pub fn difference(self, other: List<T>) -> List<T>
+    where
+        T: Ord,
+    {
+        self.difference_by(other, T::cmp)
+    }
+
+    pub fn difference_by<F>(self, other: List<T>, compare: F) -> List<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.combine_sorted(other, compare, SortedOp::Difference)
+    }
+
+    /// Elements which are only in one of the lists
+    pub fn symmetric_difference(self, other: List<T>) -> List<T>
+    where
+        T: Ord,
+    {
+        self.symmetric_difference_by(other, T::cmp)
+    }
+
+    pub fn symmetric_difference_by<F>(self, other: List<T>, compare: F) -> List<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.combine_sorted(other, compare, SortedOp::SymmetricDifference)
+    }
+
+    /// Unlinks the head node (with its next cleared) without dropping it
+    fn pop_node(&mut self) -> Option<Box<Node<T>>> {
+        self.head.take().map(|mut node| {
+            self.head = node.next.take();
+            self.len -= 1;
+            node
+        })
+    }
+
+    /// Same nodes in reverse order
+    fn reversed(mut self) -> List<T> {
+        let mut list = List::new();
+        while let Some(node) = self.pop_node() {
+            list.push_node(node);
+        }
+        list
+    }
+
+    fn push_node(&mut self, mut node: Box<Node<T>>) {
+        node.next = self.head.take();
+        self.head = Some(node);
+        self.len += 1;
+    }
+
+    fn combine_sorted<F>(mut self, mut other: List<T>, mut compare: F, op: SortedOp) -> List<T>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        use SortedOp::*;
+        let keep_only_self = matches!(op, Merge | Union | Difference | SymmetricDifference);
+        let keep_only_other = matches!(op, Merge | Union | SymmetricDifference);
+        let keep_both = matches!(op, Union | Intersection);
+
+        let mut result = List::new();
+        let mut len = 0;
+        let mut tail = &mut result.head;
+        while let (Some(a), Some(b)) = (&self.head, &other.head) {
+            let (node, keep) = match compare(&a.elem, &b.elem) {
+                // NOTE: merge doesn't pair up equal ones, they are all kept with self's first
+                Ordering::Less | Ordering::Equal if op == Merge => (self.pop_node(), true),
+                Ordering::Less => (self.pop_node(), keep_only_self),
+                Ordering::Greater => (other.pop_node(), keep_only_other),
+                Ordering::Equal => {
+                    // other's one of the pair gets dropped here
+                    other.pop_node();
+                    (self.pop_node(), keep_both)
+                },
+            };
+            if let (Some(node), true) = (node, keep) {
+                tail = &mut tail.insert(node).next;
+                len += 1;
+            }
+        }
+
+        // at most one of them has nodes left, and none of those have a pair
+        for (rest, keep) in [(&mut self, keep_only_self), (&mut other, keep_only_other)] {
+            if keep && rest.head.is_some() {
+                *tail = rest.head.take();
+                len += rest.len;
+                rest.len = 0;
+                break;
+            }
+        }
+        result.len = len;
+        result
+    }
+}
+
+pub struct ExtractIf<'a, T, F> {
+    // NOTE: Option is only there to move the reference out for moving forward (see CursorMut), it
+    // is always Some
+    link: Option<&'a mut Link<T>>,
+    len: &'a mut usize,
+    pred: F,
+}
+
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut link = self.link.take().expect("extract_if always has the link");
+        // NOTE: same as retain_mut(), pred looks at the node while it's still linked
+        while let Some(node) = link.as_mut() {
+            if (self.pred)(&mut node.elem) {
+                let mut node = link.take().expect("checked above");
+                *link = node.next.take();
+                *self.len -= 1;
+                self.link = Some(link);
+                return Some(node.elem);
+            }
+            link = &mut link.as_mut().expect("checked above").next;
+        }
+        self.link = Some(link);
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // NOTE: len is the whole list's, which is the upper bound of what's left in front
+        (0, Some(*self.len))
+    }
+}
+
+impl<T, F> FusedIterator for ExtractIf<'_, T, F> where F: FnMut(&mut T) -> bool {}
+
+pub struct Drain<'a, T> {
+    list: &'a mut List<T>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        // NOTE: popping rather than replacing the list, so that the list keeps its node pool (and
+        // the nodes go back to it)
+        while self.list.pop().is_some() {}
+    }
+}
+
+// NOTE: none of the traits below recurse through Node.next (derive would), so that they work for
+// lists of any length, just like Drop below
+
+impl<T> List<T> {
+    /// Returns the link after the last node (which is head itself for empty list)
+    fn last_link(&mut self) -> &mut Link<T> {
+        let mut link = &mut self.head;
+        while let Some(node) = link {
+            link = &mut node.next;
+        }
+        link
+    }
+}
+
+impl<T: Clone> Clone for List<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Debug> Debug for List<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// How DisplayList lays the elements out
+///
+/// Alternate flag (`{:#}`) only changes the Brackets style, Arrows and Lines look the same with
+/// it. Either way the flag (like width, precision etc.) is passed on to every element
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayStyle {
+    /// `[a, b, c]`, or one element per indented line with `{:#}`
+    #[default]
+    Brackets,
+    /// `a -> b -> c -> ∅`
+    Arrows,
+    /// every element on its own line
+    Lines,
+}
+
+/// Displays the list in the chosen style, see List::display()
+pub struct DisplayList<'a, T> {
+    list: &'a List<T>,
+    style: DisplayStyle,
+    max_shown: Option<usize>,
+}
+
+impl<T> List<T> {
+    /// Returns adapter to display the list with, by default it's the same as list's own Display
+    pub fn display(&self) -> DisplayList<'_, T> {
+        DisplayList {
+            list: self,
+            style: DisplayStyle::default(),
+            max_shown: None,
+        }
+    }
+}
+
+impl<T> DisplayList<'_, T> {
+    pub fn style(mut self, style: DisplayStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Lists longer than `max_shown` are shown with at most `max_shown` elements: the first
+    /// `max_shown - 1` ones, `…`, the last one and the length, e.g. `[a, b, …, z] (len 10000)`.
+    /// With 0 only `…` and the length are shown
+    pub fn truncate(mut self, max_shown: usize) -> Self {
+        self.max_shown = Some(max_shown);
+        self
+    }
+}
+
+impl<T: Display> Display for DisplayList<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let len = self.list.len;
+        let truncated = self.max_shown.filter(|&max| len > max);
+        let shown = truncated.map_or(len, |max| max.saturating_sub(1));
+        let last = match truncated {
+            Some(max) if max > 0 => self.list.iter().last(),
+            _ => None,
+        };
+        // NOTE: None stands for the `…` in place of the elements left out
+        let items = self
+            .list
+            .iter()
+            .take(shown)
+            .map(Some)
+            .chain(truncated.map(|_| None))
+            .chain(last.map(Some));
+
+        let pretty = f.alternate() && self.style == DisplayStyle::Brackets;
+        let (open, separator) = match self.style {
+            DisplayStyle::Brackets if pretty => ("[", "\n    "),
+            DisplayStyle::Brackets => ("[", ", "),
+            DisplayStyle::Arrows => ("", " -> "),
+            DisplayStyle::Lines => ("", "\n"),
+        };
+        f.write_str(open)?;
+        for (i, item) in items.enumerate() {
+            if i > 0 || pretty {
+                f.write_str(separator)?;
+            }
+            // NOTE: element gets the same formatter, so width, precision etc. apply to each one
+            match item {
+                Some(elem) => elem.fmt(f)?,
+                None => f.write_str("…")?,
+            }
+            if pretty {
+                f.write_str(",")?;
+            }
+        }
+        match self.style {
+            DisplayStyle::Brackets if pretty && len > 0 => f.write_str("\n]")?,
+            DisplayStyle::Brackets => f.write_str("]")?,
+            DisplayStyle::Arrows if len > 0 => f.write_str(" -> ∅")?,
+            DisplayStyle::Arrows => f.write_str("∅")?,
+            DisplayStyle::Lines => {},
+        }
+        if truncated.is_some() {
+            let separator = if self.style == DisplayStyle::Lines { "\n" } else { " " };
+            write!(f, "{}(len {})", separator, len)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.display().fmt(f)
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for List<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // NOTE: length is what keeps [[1], []] and [[], [1]] from hashing the same, like for Vec
+        state.write_usize(self.len);
+        for elem in self.iter() {
+            elem.hash(state);
+        }
+    }
+}
+
+/// Appends the elements at the end of the list, keeping their order
+impl<T> Extend<T> for List<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut added = 0;
+        let mut link = self.last_link();
+        for elem in iter {
+            link = &mut link.insert(Box::new(Node { elem, next: None })).next;
+            added += 1;
+        }
+        self.len += added;
+    }
+}
+
+/// Collected list iterates in the same order as the source iterator
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        let mut current = self.head.take();
+        while let Some(ref mut boxed_node) = current {
+            current = boxed_node.as_mut().next.take();
+            // boxed_node gets dropped here
+        }
+    }
+}
+
+/// List split around a focus, which can move both ways
+///
+/// Elements before the focus are kept in `left` in reverse order (closest one at its head), the
+/// focus and everything after it in `right`. Moving the focus just moves one node from the head of
+/// one list to the other
+pub struct Zipper<T> {
+    left: List<T>,
+    right: List<T>,
+}
+
+impl<T> List<T> {
+    /// Returns zipper focused on the head
+    pub fn zipper(self) -> Zipper<T> {
+        Zipper {
+            left: List::new(),
+            right: self,
+        }
+    }
+}
+
+impl<T> Zipper<T> {
+    /// Focus is None once it's moved past the last element
+    pub fn focus(&self) -> Option<&T> {
+        self.right.peek()
+    }
+
+    pub fn focus_mut(&mut self) -> Option<&mut T> {
+        self.right.peek_mut()
+    }
+
+    /// Number of elements before the focus
+    pub fn index(&self) -> usize {
+        self.left.len
+    }
+
+    pub fn len(&self) -> usize {
+        self.left.len + self.right.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty() && self.right.is_empty()
+    }
+
+    /// Moves focus to the previous element, returns false if it's already at the head
+    pub fn move_left(&mut self) -> bool {
+        match self.left.pop_node() {
+            Some(node) => {
+                self.right.push_node(node);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Moves focus to the next element (or past the last one), returns false if it's already past
+    /// the last one
+    pub fn move_right(&mut self) -> bool {
+        match self.right.pop_node() {
+            Some(node) => {
+                self.left.push_node(node);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Inserts the element before the focus, and focuses on it
+    pub fn insert(&mut self, elem: T) {
+        self.right.push(elem);
+    }
+
+    /// Removes the focused element, the next one gets the focus
+    pub fn delete(&mut self) -> Option<T> {
+        self.right.pop()
+    }
+
+    /// Replaces the focused element and returns the old one, `elem` is handed back if there's no
+    /// focus
+    pub fn replace(&mut self, elem: T) -> Result<T, T> {
+        match self.right.peek_mut() {
+            Some(focus) => Ok(mem::replace(focus, elem)),
+            None => Err(elem),
+        }
+    }
+
+    /// Puts the list back together, O(index())
+    pub fn into_list(mut self) -> List<T> {
+        while self.move_left() {}
+        self.right
+    }
+}
+
+/// Undo log entry, each one reverts a single mutation made through a Transaction
+enum Undo<T> {
+    /// node was pushed onto the head
+    Push,
+    /// node was popped off the head, and goes back there
+    Pop(Box<Node<T>>),
+    /// head element was handed out by peek_mut(), this is its previous value
+    Replace(T),
+    /// these nodes were split off the tail end
+    Split(List<T>),
+    /// nodes from this index on were merged in
+    Merge(usize),
+}
+
+/// Guard returned by List::begin(), every mutation made through it is undone when it's dropped
+/// without commit()
+///
+/// Nothing gets cloned to make that possible (apart from peek_mut()), the nodes which are removed
+/// from the list are kept in the undo log instead. That's why pop() and split_*() hand out
+/// references, and the removed elements are only handed back once the outermost transaction
+/// commits (see Removed)
+pub struct Transaction<'a, T> {
+    list: &'a mut List<T>,
+    log: Vec<Undo<T>>,
+    // NOTE: nested transaction hands its log over to the parent on commit, so that it can still
+    // be undone if the parent gets rolled back
+    parent: Option<&'a mut Vec<Undo<T>>>,
+}
+
+/// What Transaction::commit() hands back, in the order it was removed
+#[derive(Debug)]
+pub struct Removed<T> {
+    /// elements taken by pop()
+    pub popped: Vec<T>,
+    /// lists split off by split_*()
+    pub split: Vec<List<T>>,
+}
+
+impl<T> List<T> {
+    /// Starts a transaction, see Transaction
+    pub fn begin(&mut self) -> Transaction<'_, T> {
+        Transaction {
+            list: self,
+            log: Vec::new(),
+            parent: None,
+        }
+    }
+}
+
+impl<T> Transaction<'_, T> {
+    /// Starts a nested transaction, which can be rolled back on its own
+    pub fn begin(&mut self) -> Transaction<'_, T> {
+        Transaction {
+            list: self.list,
+            log: Vec::new(),
+            parent: Some(&mut self.log),
+        }
+    }
+
+    /// Keeps the changes and returns what was removed from the list
+    ///
+    /// If this one is nested, changes are handed to the parent transaction instead (as it can
+    /// still be rolled back), and nothing is returned till the outermost one commits
+    pub fn commit(mut self) -> Removed<T> {
+        let mut removed = Removed {
+            popped: Vec::new(),
+            split: Vec::new(),
+        };
+        match self.parent.take() {
+            Some(parent) => parent.append(&mut self.log),
+            None => {
+                for undo in self.log.drain(..) {
+                    match undo {
+                        Undo::Pop(node) => removed.popped.push(node.elem),
+                        Undo::Split(list) => removed.split.push(list),
+                        Undo::Push | Undo::Replace(_) | Undo::Merge(_) => {},
+                    }
+                }
+            },
+        }
+        removed
+    }
+
+    /// Undoes the changes, same as dropping the guard
+    pub fn rollback(self) {}
+
+    /// List as it is with the changes made so far
+    pub fn list(&self) -> &List<T> {
+        self.list
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.list.peek()
+    }
+
+    pub fn push(&mut self, elem: T) {
+        self.list.push(elem);
+        self.log.push(Undo::Push);
+    }
+
+    /// Removes the head, which is kept around till commit
+    pub fn pop(&mut self) -> Option<&T> {
+        let mut node = self.list.head.take()?;
+        self.list.head = node.next.take();
+        self.list.len -= 1;
+        self.log.push(Undo::Pop(node));
+        match self.log.last() {
+            Some(Undo::Pop(node)) => Some(&node.elem),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Appends `list` after the tail
+    pub fn merge(&mut self, list: List<T>) {
+        self.log.push(Undo::Merge(self.list.len));
+        self.list.merge(list);
+    }
+
+    /// See List::split_off()
+    pub fn split_off(&mut self, index: usize) -> Option<&List<T>> {
+        let list = self.list.split_off(index);
+        self.log_split(list)
+    }
+
+    /// See List::split_when()
+    pub fn split_when<F>(&mut self, pred: F) -> Option<&List<T>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let list = self.list.split_when(pred);
+        self.log_split(list)
+    }
+
+    /// See List::split_after_when()
+    pub fn split_after_when<F>(&mut self, pred: F) -> Option<&List<T>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let list = self.list.split_after_when(pred);
+        self.log_split(list)
+    }
+
+    fn log_split(&mut self, list: Option<List<T>>) -> Option<&List<T>> {
+        self.log.push(Undo::Split(list?));
+        match self.log.last() {
+            Some(Undo::Split(list)) => Some(list),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<T: Clone> Transaction<'_, T> {
+    /// Head element's current value is cloned into the undo log before handing it out
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        let old = self.list.peek()?.clone();
+        self.log.push(Undo::Replace(old));
+        self.list.peek_mut()
+    }
+}
+
+impl<T: PartialEq> Transaction<'_, T> {
+    /// See List::split_at()
+    pub fn split_at(&mut self, elem: T) -> Option<&List<T>> {
+        let list = self.list.split_at(elem);
+        self.log_split(list)
+    }
+
+    /// See List::split_next()
+    pub fn split_next(&mut self, elem: T) -> Option<&List<T>> {
+        let list = self.list.split_next(elem);
+        self.log_split(list)
+    }
+}
+
+impl<T> Drop for Transaction<'_, T> {
+    fn drop(&mut self) {
+        // NOTE: entries are undone newest first, so the list is always in the same state as it
+        // was right after the mutation being undone
+        while let Some(undo) = self.log.pop() {
+            match undo {
+                Undo::Push => {
+                    self.list.pop();
+                },
+                Undo::Pop(mut node) => {
+                    node.next = self.list.head.take();
+                    self.list.head = Some(node);
+                    self.list.len += 1;
+                },
+                Undo::Replace(old) => {
+                    if let Some(elem) = self.list.peek_mut() {
+                        *elem = old;
+                    }
+                },
+                Undo::Split(list) => self.list.merge(list),
+                Undo::Merge(index) => {
+                    self.list.split_off(index);
+                },
+            }
+        }
+    }
+}
+
+/// What BoundedList does with the elements which don't fit under its limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// leave the list as it is and hand the new element(s) back
+    Reject,
+    /// make room by dropping elements from the tail
+    DropOldest,
+    /// make room by dropping elements from the head
+    DropNewest,
+}
+
+/// Stack on top of List which never holds more than `limit` elements
+pub struct BoundedList<T> {
+    list: List<T>,
+    limit: usize,
+    policy: OverflowPolicy,
+}
+
+impl<T> BoundedList<T> {
+    pub fn new(limit: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            list: List::new(),
+            limit,
+            policy,
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.list.len >= self.limit
+    }
+
+    /// Pushes the element and returns the one which didn't fit, if any: `elem` itself with
+    /// Reject, the tail with DropOldest (which is O(n)) and the previous head with DropNewest
+    pub fn push(&mut self, elem: T) -> Option<T> {
+        if !self.is_full() {
+            self.list.push(elem);
+            return None;
+        }
+        // NOTE: with limit of 0 there is nothing to drop to make room, new element is the one
+        // which doesn't fit whatever the policy is
+        if self.limit == 0 {
+            return Some(elem);
+        }
+        let dropped = match self.policy {
+            OverflowPolicy::Reject => return Some(elem),
+            OverflowPolicy::DropOldest => self.list.remove_at(self.list.len - 1),
+            OverflowPolicy::DropNewest => self.list.pop(),
+        };
+        self.list.push(elem);
+        dropped
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.list.pop()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.list.peek()
+    }
+
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.list.peek_mut()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+
+    pub fn as_list(&self) -> &List<T> {
+        &self.list
+    }
+
+    pub fn into_list(self) -> List<T> {
+        self.list
+    }
+
+    /// Appends `list` after the tail, and returns the elements which didn't fit. Those are the
+    /// ones from the tail end with Reject and DropOldest, and from the head end with DropNewest
+    pub fn merge(&mut self, list: List<T>) -> List<T> {
+        self.list.merge(list);
+        let len = self.list.len;
+        if len <= self.limit {
+            return List::new();
+        }
+        match self.policy {
+            OverflowPolicy::Reject | OverflowPolicy::DropOldest => {
+                self.list.split_off(self.limit).unwrap_or_default()
+            },
+            OverflowPolicy::DropNewest => {
+                let kept = self.list.split_off(len - self.limit).unwrap_or_default();
+                mem::replace(&mut self.list, kept)
+            },
+        }
+    }
+
+    /// See List::split_off(), new list gets the same limit and policy
+    pub fn split_off(&mut self, index: usize) -> Option<Self> {
+        self.list.split_off(index).map(|list| self.bounded(list))
     }
-}
 
-impl<'a, T> Iterator for IterMut<'a, T> {
-    type Item = &'a mut T;
+    /// See List::split_when(), new list gets the same limit and policy
+    pub fn split_when<F>(&mut self, pred: F) -> Option<Self>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.list.split_when(pred).map(|list| self.bounded(list))
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.next.take().map(|node| {
-            // self.next = node.next.as_mut().map(|node| node.as_mut());
-            // self.next = node.next.as_mut().map(|node| &mut **node);
-            //
-            // NOTE: both the above lines are correct, here as_deref() is deref coercion for
-            // &**node
-            //
-            // self.next = node.next.as_deref_mut();
-            // or
-            self.next = node.next.as_mut().map::<&mut Node<T>, _>(|node| node);
-            &mut node.elem
-        })
+    /// See List::split_after_when(), new list gets the same limit and policy
+    pub fn split_after_when<F>(&mut self, pred: F) -> Option<Self>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.list.split_after_when(pred).map(|list| self.bounded(list))
     }
-}
 
-impl<T> Iterator for List<T> {
-    type Item = T;
+    // NOTE: splitting never adds elements, so whatever is split off fits under the same limit
+    fn bounded(&self, list: List<T>) -> Self {
+        Self {
+            list,
+            limit: self.limit,
+            policy: self.policy,
+        }
+    }
+}
 
-    // NOTE: <!-- This comment would be updated on other iter kinds of Iterator impl -->
-    // While this implementation works, I guess the one reason the writer showed to write it that way is
-    // because it shows the clear ownership transfer of the list to the iterator.
-    // With the below approach it is not very clear and may give sense that same list object, which was
-    // used to create iterator can be used again to perform list operations, which isn't true if list
-    // is created with into_iter() method, which will take ownership of the list object and thus will
-    // make the list object unusable outside of iterator
-    fn next(&mut self) -> Option<Self::Item> {
-        self.head.take().map(|node| {
-            self.head = node.next;
-            node.elem
-        })
+impl<T: PartialEq> BoundedList<T> {
+    /// See List::split_at(), new list gets the same limit and policy
+    pub fn split_at(&mut self, elem: T) -> Option<Self> {
+        self.list.split_at(elem).map(|list| self.bounded(list))
     }
 
-    // NOTE: iter() can't be done directly because traits can't hold any value on their own, and we
-    // need some cursor (variable) to tell us where iterator is pointing at some particular time
+    /// See List::split_next(), new list gets the same limit and policy
+    pub fn split_next(&mut self, elem: T) -> Option<Self> {
+        self.list.split_next(elem).map(|list| self.bounded(list))
+    }
 }
 
-impl<T> Drop for List<T> {
-    fn drop(&mut self) {
-        let mut current = self.head.take();
-        while let Some(ref mut boxed_node) = current {
-            current = boxed_node.as_mut().next.take();
-            // boxed_node gets dropped here
-        }
+impl<T: Debug> Debug for BoundedList<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.list.fmt(f)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::List;
+    use std::panic::{self, AssertUnwindSafe};
+
+    use super::{BoundedList, DisplayStyle, List, OverflowPolicy};
+
+    /// Walks the nodes and checks that len is right
+    pub(super) fn assert_len<T>(list: &List<T>) {
+        let mut count = 0;
+        let mut link = &list.head;
+        while let Some(node) = link {
+            count += 1;
+            link = &node.next;
+        }
+        assert_eq!(list.len(), count);
+        assert_eq!(list.is_empty(), count == 0);
+    }
 
     #[test]
     fn second_list() {
@@ -421,9 +1826,9 @@ mod test {
         list.push(10);
         list.push(20);
         assert_eq!(list.peek(), Some(&20));
-        list.peek_mut().map(|elem| {
+        if let Some(elem) = list.peek_mut() {
             *elem = 30;
-        });
+        }
         _ = list.pop();
         assert_eq!(list.peek_mut(), Some(&mut 10));
     }
@@ -454,6 +1859,40 @@ mod test {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn test_for_into_iter() {
+        let mut list = List::new();
+        list.push(10);
+        list.push(20);
+        list.push(30);
+
+        // for loop goes through IntoIterator too, which takes ownership of List
+        let mut elems = Vec::new();
+        for elem in list {
+            elems.push(elem);
+        }
+        assert_eq!(elems, vec![30, 20, 10]);
+    }
+
+    #[test]
+    fn test_for_by_ref() {
+        let mut list = List::new();
+        list.push(10);
+        list.push(20);
+        list.push(30);
+
+        for elem in &mut list {
+            *elem += 1;
+        }
+        let mut elems = Vec::new();
+        for elem in &list {
+            elems.push(*elem);
+        }
+        assert_eq!(elems, vec![31, 21, 11]);
+        // list is still usable
+        assert_eq!(list.pop(), Some(31));
+    }
+
     #[test]
     fn test_iter() {
         let mut list = List::new();
@@ -481,4 +1920,825 @@ mod test {
         assert_eq!(iter.next(), Some(&mut 10));
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn test_no_partial_eq() {
+        // closures aren't PartialEq, yet the list works for them
+        let mut list: List<Box<dyn Fn() -> i32>> = List::new();
+        list.push(Box::new(|| 1));
+        list.push(Box::new(|| 2));
+        list.push(Box::new(|| 3));
+        assert_eq!(list.peek().map(|f| f()), Some(3));
+
+        let mut rest = list.split_when(|f| f() == 2).unwrap();
+        assert_eq!(rest.pop().map(|f| f()), Some(2));
+        assert_eq!(rest.pop().map(|f| f()), Some(1));
+        assert!(rest.pop().is_none());
+        assert_eq!(list.into_iter().map(|f| f()).collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn test_split_when() {
+        let mut list = List::new();
+        list.push(-2);
+        list.push(-1);
+        list.push(1);
+        list.push(2);
+
+        assert!(list.split_when(|elem| *elem > 10).is_none());
+
+        let mut negatives = list.split_when(|elem| *elem < 0).unwrap();
+        assert_eq!(negatives.pop(), Some(-1));
+        assert_eq!(negatives.pop(), Some(-2));
+        assert_eq!(negatives.pop(), None);
+
+        let mut all = list.split_when(|_| true).unwrap();
+        assert_eq!(list.pop(), None);
+        assert_eq!(all.pop(), Some(2));
+        assert_eq!(all.pop(), Some(1));
+        assert_eq!(all.pop(), None);
+    }
+
+    #[test]
+    fn test_split_after_when() {
+        let mut list = List::new();
+        list.push(3);
+        list.push(2);
+        list.push(1);
+
+        assert!(list.split_after_when(|elem| *elem == 10).is_none());
+
+        let mut empty = list.split_after_when(|elem| *elem == 3).unwrap();
+        assert_eq!(empty.pop(), None);
+
+        let mut rest = list.split_after_when(|elem| *elem % 2 == 1).unwrap();
+        assert_eq!(list.pop(), Some(1));
+        assert_eq!(list.pop(), None);
+        assert_eq!(rest.pop(), Some(2));
+        assert_eq!(rest.pop(), Some(3));
+        assert_eq!(rest.pop(), None);
+    }
+
+    #[test]
+    fn test_traits() {
+        let list = [1, 2, 3].into_iter().collect::<List<_>>();
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(format!("{:?}", List::<i32>::default()), "[]");
+
+        let mut cloned = list.clone();
+        assert_eq!(cloned, list);
+        cloned.extend([4, 5]);
+        assert_eq!(format!("{:?}", cloned), "[1, 2, 3, 4, 5]");
+        assert_ne!(cloned, list);
+        assert!(list < cloned);
+        assert!([2].into_iter().collect::<List<_>>() > cloned);
+        assert_eq!(list.partial_cmp(&list.clone()), Some(std::cmp::Ordering::Equal));
+
+        let mut empty = List::new();
+        empty.extend(Vec::<i32>::new());
+        assert_eq!(empty, List::new());
+        empty.extend([1]);
+        assert_eq!(format!("{:?}", empty), "[1]");
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashMap;
+
+        let mut map = HashMap::new();
+        map.insert([1, 2].into_iter().collect::<List<_>>(), "a");
+        map.insert(List::new(), "b");
+        assert_eq!(map.get(&[1, 2].into_iter().collect()), Some(&"a"));
+        assert_eq!(map.get(&List::new()), Some(&"b"));
+        assert_eq!(map.get(&[2, 1].into_iter().collect()), None);
+
+        // nested lists with same flattened elements shouldn't collide
+        use std::hash::{BuildHasher, RandomState};
+        let state = RandomState::new();
+        let a = [[1].into_iter().collect(), List::new()].into_iter().collect::<List<List<i32>>>();
+        let b = [List::new(), [1].into_iter().collect()].into_iter().collect::<List<List<i32>>>();
+        assert_ne!(state.hash_one(&a), state.hash_one(&b));
+    }
+
+    #[test]
+    fn test_traits_long_list() {
+        // none of these should recurse through the nodes
+        let list = (0..1_000_000).collect::<List<u32>>();
+        let cloned = list.clone();
+        assert_eq!(cloned, list);
+        assert_eq!(cloned.cmp(&list), std::cmp::Ordering::Equal);
+
+        use std::hash::{BuildHasher, RandomState};
+        let state = RandomState::new();
+        assert_eq!(state.hash_one(&list), state.hash_one(&cloned));
+        assert!(format!("{:?}", list).ends_with("999998, 999999]"));
+    }
+
+    #[test]
+    fn test_cursor_walk() {
+        let mut list = (1..=3).collect::<List<_>>();
+        let mut cursor = list.cursor_mut();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+
+        assert!(cursor.move_next());
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert!(cursor.move_next());
+        *cursor.current().unwrap() *= 10;
+        assert!(cursor.move_next());
+        assert_eq!(cursor.peek_next(), None);
+        // stays at the last element
+        assert!(!cursor.move_next());
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 3));
+        assert_eq!(format!("{:?}", list), "[1, 20, 3]");
+
+        let mut empty = List::<i32>::new();
+        let mut cursor = empty.cursor_mut();
+        assert!(!cursor.move_next());
+        assert_eq!(cursor.current(), None);
+    }
+
+    #[test]
+    fn test_cursor_insert_remove() {
+        let mut list = List::new();
+        let mut cursor = list.cursor_mut();
+        // before the head, these work at the front
+        cursor.insert_after(3);
+        cursor.insert_after(1);
+        assert_eq!(cursor.remove_next(), Some(1));
+        cursor.insert_after(1);
+
+        cursor.move_next();
+        cursor.insert_after(2);
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.move_next();
+        cursor.insert_after(4);
+        assert_eq!(cursor.remove_next(), Some(4));
+        assert_eq!(cursor.remove_next(), None);
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+    }
+
+    #[test]
+    fn test_cursor_split_splice() {
+        let mut list = (1..=5).collect::<List<_>>();
+        let mut cursor = list.cursor_mut();
+        cursor.move_next();
+        cursor.move_next();
+        let rest = cursor.split_after();
+        assert_eq!(format!("{:?}", rest), "[3, 4, 5]");
+        assert_eq!(cursor.split_after(), List::new());
+
+        cursor.splice_after(List::new());
+        cursor.splice_after([8, 9].into_iter().collect());
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.move_next();
+        cursor.move_next();
+        cursor.splice_after(rest);
+        assert_eq!(format!("{:?}", list), "[1, 2, 8, 9, 3, 4, 5]");
+
+        let mut cursor = list.cursor_mut();
+        let all = cursor.split_after();
+        assert_eq!(format!("{:?}", all), "[1, 2, 8, 9, 3, 4, 5]");
+        assert_eq!(list, List::new());
+    }
+
+    #[test]
+    fn test_len_split() {
+        let mut list = List::new();
+        assert_len(&list);
+        for elem in 1..=5 {
+            list.push(elem);
+        }
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.pop(), Some(5));
+        assert_len(&list);
+        list.push(5);
+
+        // same edge cases as test_split() and test_split_at()
+        assert!(list.split_next(10).is_none());
+        assert_len(&list);
+        let list2 = list.split_next(1).unwrap();
+        assert_len(&list);
+        assert_len(&list2);
+        let mut moved = list.split_next(5).unwrap();
+        assert_len(&list);
+        assert_len(&moved);
+        let half = moved.split_next(3).unwrap();
+        assert_eq!((moved.len(), half.len()), (2, 2));
+        assert_len(&moved);
+        assert_len(&half);
+
+        let mut list = (1..=5).collect::<List<_>>();
+        assert!(list.split_at(10).is_none());
+        let last = list.split_at(5).unwrap();
+        assert_len(&list);
+        assert_len(&last);
+        let all = list.split_at(1).unwrap();
+        assert_eq!((list.len(), all.len()), (0, 4));
+        assert_len(&list);
+        assert_len(&all);
+
+        let mut list = (1..=5).collect::<List<_>>();
+        let rest = list.split_when(|elem| *elem > 2).unwrap();
+        assert_eq!((list.len(), rest.len()), (2, 3));
+        let mut list = (1..=5).collect::<List<_>>();
+        let rest = list.split_after_when(|elem| *elem > 2).unwrap();
+        assert_eq!((list.len(), rest.len()), (3, 2));
+        assert_len(&rest);
+
+        let mut list = (1..=5).collect::<List<_>>();
+        let all = list.early_split_at(1).unwrap();
+        assert_eq!((list.len(), all.len()), (0, 5));
+    }
+
+    #[test]
+    fn test_len_merge_cursor() {
+        let mut list = List::new();
+        list.merge(List::new());
+        assert_len(&list);
+        list.merge((1..=2).collect());
+        list.merge((3..=4).collect());
+        assert_eq!(list.len(), 4);
+        assert_len(&list);
+        list.extend([5, 6]);
+        assert_eq!(list.len(), 6);
+
+        let mut cursor = list.cursor_mut();
+        cursor.insert_after(0);
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.remove_next(), Some(2));
+        let rest = cursor.split_after();
+        assert_eq!(rest.len(), 4);
+        assert_len(&rest);
+        cursor.splice_after(rest);
+        assert_eq!(cursor.remove_next(), Some(3));
+        assert_len(&list);
+        assert_eq!(list.len(), 5);
+
+        let mut cursor = list.cursor_mut();
+        assert_eq!(cursor.split_after().len(), 5);
+        assert_len(&list);
+    }
+
+    #[test]
+    fn test_size_hint() {
+        let mut list = (1..=3).collect::<List<_>>();
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 3);
+        iter.next();
+        let cloned = iter.clone();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        assert_eq!(cloned.collect::<Vec<_>>(), vec![&2, &3]);
+        iter.next();
+        iter.next();
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+
+        let mut iter = list.iter_mut();
+        iter.next();
+        assert_eq!(iter.len(), 2);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 3);
+        iter.next();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut list = (1..=10).collect::<List<_>>();
+        list.retain(|elem| elem % 3 != 0);
+        assert_len(&list);
+        assert_eq!(format!("{:?}", list), "[1, 2, 4, 5, 7, 8, 10]");
+
+        // from the head and consecutive ones
+        list.retain(|elem| *elem > 4);
+        assert_eq!(format!("{:?}", list), "[5, 7, 8, 10]");
+        list.retain_mut(|elem| {
+            *elem *= 2;
+            *elem != 20
+        });
+        assert_len(&list);
+        assert_eq!(format!("{:?}", list), "[10, 14, 16]");
+        list.retain(|_| false);
+        assert_len(&list);
+        assert!(list.is_empty());
+
+        // long runs of dropped nodes
+        let mut list = (0..1_000_000).collect::<List<_>>();
+        list.retain(|elem| *elem == 999_999);
+        assert_eq!(format!("{:?}", list), "[999999]");
+    }
+
+    #[test]
+    fn test_extract_if() {
+        let mut list = (1..=10).collect::<List<_>>();
+        let evens = list.extract_if(|elem| *elem % 2 == 0).collect::<Vec<_>>();
+        assert_eq!(evens, vec![2, 4, 6, 8, 10]);
+        assert_len(&list);
+        assert_eq!(format!("{:?}", list), "[1, 3, 5, 7, 9]");
+
+        // lazy, only what's been yielded is removed
+        {
+            let mut extract = list.extract_if(|elem| *elem > 1);
+            assert_eq!(extract.next(), Some(3));
+            assert_eq!(extract.next(), Some(5));
+        }
+        assert_len(&list);
+        assert_eq!(format!("{:?}", list), "[1, 7, 9]");
+
+        let mut extract = list.extract_if(|_| true);
+        assert_eq!(extract.next(), Some(1));
+        assert_eq!(extract.next(), Some(7));
+        assert_eq!(extract.next(), Some(9));
+        assert_eq!(extract.next(), None);
+        assert_eq!(extract.next(), None);
+        assert!(list.is_empty());
+        assert_len(&list);
+    }
+
+    #[test]
+    fn test_retain_extract_if_panic() {
+        // nodes the predicate didn't get to (and the one it panicked on) stay in the list
+        let mut list = (1..=6).collect::<List<_>>();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            list.retain(|elem| if *elem == 4 { panic!("retain") } else { *elem != 2 })
+        }));
+        assert!(result.is_err());
+        assert_eq!(format!("{:?}", list), "[1, 3, 4, 5, 6]");
+        assert_len(&list);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            list.extract_if(|elem| if *elem == 5 { panic!("extract_if") } else { *elem == 3 })
+                .count()
+        }));
+        assert!(result.is_err());
+        assert_eq!(format!("{:?}", list), "[1, 4, 5, 6]");
+        assert_len(&list);
+
+        // panicking before the end of long list doesn't drop the rest of it recursively
+        let mut list = (0..1_000_000).collect::<List<_>>();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            list.retain(|elem| if *elem == 1 { panic!("retain") } else { true })
+        }));
+        assert!(result.is_err());
+        assert_eq!(list.len(), 1_000_000);
+        assert_len(&list);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut list = (1..=3).collect::<List<_>>();
+        let mut drain = list.drain();
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next(), Some(1));
+        drop(drain);
+        assert!(list.is_empty());
+        assert_len(&list);
+
+        list.extend([4, 5]);
+        assert_eq!(list.drain().collect::<Vec<_>>(), vec![4, 5]);
+        assert!(list.is_empty());
+        list.push(6);
+        assert_eq!(list.pop(), Some(6));
+
+        // drained nodes go to the pool, which keeps working afterwards
+        let mut list = List::with_node_pool(2);
+        list.extend([1, 2, 3]);
+        let mut drain = list.drain();
+        assert_eq!(drain.next(), Some(1));
+        drop(drain);
+        assert_len(&list);
+        assert_eq!(list.pooled_nodes(), 2);
+        list.push(4);
+        assert_eq!(list.pooled_nodes(), 1);
+        assert_eq!(list.pop(), Some(4));
+        assert_eq!(list.pooled_nodes(), 2);
+    }
+
+    #[test]
+    fn test_merge_sorted() {
+        let a = [1, 3, 5, 7].into_iter().collect::<List<_>>();
+        let b = [2, 3, 4, 8, 9].into_iter().collect::<List<_>>();
+        let merged = a.merge_sorted(b);
+        assert_len(&merged);
+        assert_eq!(format!("{:?}", merged), "[1, 2, 3, 3, 4, 5, 7, 8, 9]");
+
+        assert_eq!(List::<i32>::new().merge_sorted(List::new()), List::new());
+        let merged = List::new().merge_sorted([1, 2].into_iter().collect());
+        assert_len(&merged);
+        assert_eq!(format!("{:?}", merged), "[1, 2]");
+
+        // equal keys keep self's first
+        let a = [(1, 'a'), (2, 'a')].into_iter().collect::<List<_>>();
+        let b = [(1, 'b'), (2, 'b')].into_iter().collect::<List<_>>();
+        let merged = a.merge_sorted_by(b, |x, y| x.0.cmp(&y.0));
+        assert_eq!(
+            merged.into_iter().collect::<Vec<_>>(),
+            vec![(1, 'a'), (1, 'b'), (2, 'a'), (2, 'b')]
+        );
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let make = |elems: &[i32]| elems.iter().copied().collect::<List<_>>();
+        let a = || make(&[1, 1, 2, 4, 6, 8]);
+        let b = || make(&[1, 3, 4, 4, 8, 9]);
+
+        let union = a().union(b());
+        assert_len(&union);
+        assert_eq!(format!("{:?}", union), "[1, 1, 2, 3, 4, 4, 6, 8, 9]");
+
+        let intersection = a().intersection(b());
+        assert_len(&intersection);
+        assert_eq!(format!("{:?}", intersection), "[1, 4, 8]");
+
+        let difference = a().difference(b());
+        assert_len(&difference);
+        assert_eq!(format!("{:?}", difference), "[1, 2, 6]");
+        assert_eq!(format!("{:?}", b().difference(a())), "[3, 4, 9]");
+
+        let symmetric = a().symmetric_difference(b());
+        assert_len(&symmetric);
+        assert_eq!(format!("{:?}", symmetric), "[1, 2, 3, 4, 6, 9]");
+
+        assert_eq!(a().intersection(List::new()), List::new());
+        assert_eq!(a().difference(List::new()), a());
+        assert_eq!(List::new().union(b()), b());
+
+        // descending order through _by versions
+        let desc = |elems: &[i32]| elems.iter().rev().copied().collect::<List<_>>();
+        let union = desc(&[1, 2, 4]).union_by(desc(&[2, 3]), |x, y| y.cmp(x));
+        assert_eq!(format!("{:?}", union), "[4, 3, 2, 1]");
+        let intersection = desc(&[1, 2, 4]).intersection_by(desc(&[2, 3]), |x, y| y.cmp(x));
+        assert_eq!(format!("{:?}", intersection), "[2]");
+        let difference = desc(&[1, 2, 4]).difference_by(desc(&[2, 3]), |x, y| y.cmp(x));
+        assert_eq!(format!("{:?}", difference), "[4, 1]");
+        let symmetric = desc(&[1, 2, 4]).symmetric_difference_by(desc(&[2, 3]), |x, y| y.cmp(x));
+        assert_eq!(format!("{:?}", symmetric), "[4, 3, 1]");
+    }
+
+    #[test]
+    fn test_get() {
+        let mut list = (0..5).collect::<List<_>>();
+        assert_eq!(list.get(0), Some(&0));
+        assert_eq!(list.get(4), Some(&4));
+        assert_eq!(list.get(5), None);
+        *list.get_mut(2).unwrap() = 20;
+        assert_eq!(list.get_mut(5), None);
+        assert_eq!(format!("{:?}", list), "[0, 1, 20, 3, 4]");
+    }
+
+    #[test]
+    fn test_insert_remove_at() {
+        let mut list = List::new();
+        assert_eq!(list.insert_at(1, 'x'), Err('x'));
+        assert_eq!(list.insert_at(0, 'b'), Ok(()));
+        assert_eq!(list.insert_at(0, 'a'), Ok(()));
+        // at len is appending
+        assert_eq!(list.insert_at(2, 'd'), Ok(()));
+        assert_eq!(list.insert_at(2, 'c'), Ok(()));
+        assert_len(&list);
+        assert_eq!(format!("{:?}", list), "['a', 'b', 'c', 'd']");
+
+        assert_eq!(list.remove_at(4), None);
+        assert_eq!(list.remove_at(3), Some('d'));
+        assert_eq!(list.remove_at(1), Some('b'));
+        assert_eq!(list.remove_at(0), Some('a'));
+        assert_len(&list);
+        assert_eq!(format!("{:?}", list), "['c']");
+        assert_eq!(list.remove_at(0), Some('c'));
+        assert_eq!(list.remove_at(0), None);
+        assert_len(&list);
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut list = (0..5).collect::<List<_>>();
+        assert!(list.split_off(6).is_none());
+        assert_len(&list);
+
+        let empty = list.split_off(5).unwrap();
+        assert!(empty.is_empty());
+        assert_len(&empty);
+
+        let rest = list.split_off(2).unwrap();
+        assert_len(&list);
+        assert_len(&rest);
+        assert_eq!(format!("{:?} {:?}", list, rest), "[0, 1] [2, 3, 4]");
+
+        let all = list.split_off(0).unwrap();
+        assert_len(&list);
+        assert_len(&all);
+        assert_eq!(format!("{:?} {:?}", list, all), "[] [0, 1]");
+    }
+
+    #[test]
+    fn test_node_pool() {
+        let mut list = List::with_node_pool(2);
+        for elem in 0..4 {
+            list.push(elem.to_string());
+        }
+        for _ in 0..4 {
+            list.pop();
+        }
+        // only 2 of the popped nodes are kept
+        assert_eq!(list.pooled_nodes(), 2);
+        assert_len(&list);
+
+        list.push("a".to_string());
+        assert_eq!(list.pooled_nodes(), 1);
+        let node = list.peek().unwrap() as *const String;
+        list.pop();
+        list.push("b".to_string());
+        // same node is handed back out
+        assert_eq!(list.peek().unwrap() as *const String, node);
+        assert_eq!(list.peek().map(String::as_str), Some("b"));
+
+        list.shrink_pool();
+        assert_eq!(list.pooled_nodes(), 0);
+        assert_eq!(list.pop(), Some("b".to_string()));
+        assert_eq!(list.pooled_nodes(), 1);
+        assert_len(&list);
+
+        let mut plain = List::new();
+        plain.push(1);
+        plain.pop();
+        assert_eq!(plain.pooled_nodes(), 0);
+    }
+
+    #[test]
+    fn test_display() {
+        let list = (1..=3).collect::<List<_>>();
+        assert_eq!(list.to_string(), "[1, 2, 3]");
+        assert_eq!(format!("{:#}", list), "[\n    1,\n    2,\n    3,\n]");
+        assert_eq!(format!("{:02}", list), "[01, 02, 03]");
+        assert_eq!(
+            list.display().style(DisplayStyle::Arrows).to_string(),
+            "1 -> 2 -> 3 -> ∅"
+        );
+        assert_eq!(list.display().style(DisplayStyle::Lines).to_string(), "1\n2\n3");
+
+        let empty = List::<i32>::new();
+        assert_eq!(empty.to_string(), "[]");
+        assert_eq!(format!("{:#}", empty), "[]");
+        assert_eq!(empty.display().style(DisplayStyle::Arrows).to_string(), "∅");
+        assert_eq!(empty.display().style(DisplayStyle::Lines).to_string(), "");
+    }
+
+    #[test]
+    fn test_display_truncate() {
+        let list = (1..=10000).collect::<List<_>>();
+        assert_eq!(list.display().truncate(3).to_string(), "[1, 2, …, 10000] (len 10000)");
+        assert_eq!(
+            list.display()
+                .truncate(2)
+                .style(DisplayStyle::Arrows)
+                .to_string(),
+            "1 -> … -> 10000 -> ∅ (len 10000)"
+        );
+        assert_eq!(
+            list.display()
+                .truncate(2)
+                .style(DisplayStyle::Lines)
+                .to_string(),
+            "1\n…\n10000\n(len 10000)"
+        );
+        assert_eq!(
+            format!("{:#}", list.display().truncate(1)),
+            "[\n    …,\n    10000,\n] (len 10000)"
+        );
+
+        // never more than max_shown elements
+        let five = (1..=5).collect::<List<_>>();
+        assert_eq!(five.display().truncate(1).to_string(), "[…, 5] (len 5)");
+        assert_eq!(five.display().truncate(0).to_string(), "[…] (len 5)");
+        assert_eq!(
+            five.display()
+                .truncate(0)
+                .style(DisplayStyle::Arrows)
+                .to_string(),
+            "… -> ∅ (len 5)"
+        );
+        // alternate flag doesn't change arrows
+        assert_eq!(
+            format!("{:#}", five.display().style(DisplayStyle::Arrows)),
+            "1 -> 2 -> 3 -> 4 -> 5 -> ∅"
+        );
+
+        // short enough lists are left alone
+        let short = (1..=3).collect::<List<_>>();
+        assert_eq!(short.display().truncate(3).to_string(), "[1, 2, 3]");
+    }
+
+    #[test]
+    fn test_bounded_push() {
+        let mut list = BoundedList::new(3, OverflowPolicy::Reject);
+        for elem in 1..=3 {
+            assert_eq!(list.push(elem), None);
+        }
+        assert!(list.is_full());
+        assert_eq!(list.push(4), Some(4));
+        assert_eq!(format!("{:?}", list), "[3, 2, 1]");
+
+        let mut list = BoundedList::new(3, OverflowPolicy::DropOldest);
+        push_all(&mut list, 1..=3);
+        assert_eq!(list.push(4), Some(1));
+        assert_eq!(format!("{:?}", list), "[4, 3, 2]");
+        assert_len(list.as_list());
+
+        let mut list = BoundedList::new(3, OverflowPolicy::DropNewest);
+        push_all(&mut list, 1..=3);
+        assert_eq!(list.push(4), Some(3));
+        assert_eq!(format!("{:?}", list), "[4, 2, 1]");
+        assert_len(list.as_list());
+
+        let mut list = BoundedList::new(0, OverflowPolicy::DropOldest);
+        assert_eq!(list.push(1), Some(1));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_bounded_merge() {
+        for (policy, kept, dropped) in [
+            (OverflowPolicy::Reject, "[3, 2, 1, 6]", "[5, 4]"),
+            (OverflowPolicy::DropOldest, "[3, 2, 1, 6]", "[5, 4]"),
+            (OverflowPolicy::DropNewest, "[1, 6, 5, 4]", "[3, 2]"),
+        ] {
+            let mut list = BoundedList::new(4, policy);
+            push_all(&mut list, 1..=3);
+            let rest = list.merge([6, 5, 4].into_iter().collect());
+            assert_eq!(format!("{:?} {:?}", list, rest), format!("{} {}", kept, dropped));
+            assert_len(list.as_list());
+            assert_len(&rest);
+        }
+
+        let mut list = BoundedList::new(4, OverflowPolicy::Reject);
+        assert!(list.merge([1, 2].into_iter().collect()).is_empty());
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn test_bounded_split() {
+        let mut list = BoundedList::new(4, OverflowPolicy::Reject);
+        push_all(&mut list, 1..=4);
+        let mut rest = list.split_at(2).unwrap();
+        assert_eq!(format!("{:?} {:?}", list, rest), "[4, 3] [2, 1]");
+        assert_eq!((rest.limit(), rest.policy()), (4, OverflowPolicy::Reject));
+        push_all(&mut rest, 5..=7);
+        assert_eq!(format!("{:?}", rest), "[6, 5, 2, 1]");
+        assert!(list.split_when(|&elem| elem > 4).is_none());
+        assert_eq!(list.split_off(1).map(|list| list.len()), Some(1));
+        assert_len(list.as_list());
+    }
+
+    fn push_all<T>(list: &mut BoundedList<T>, elems: impl IntoIterator<Item = T>) {
+        for elem in elems {
+            list.push(elem);
+        }
+    }
+
+    #[test]
+    fn test_transaction_rollback() {
+        let mut list = (1..=5).collect::<List<_>>();
+        {
+            let mut tx = list.begin();
+            tx.push(0);
+            assert_eq!(tx.pop(), Some(&0));
+            assert_eq!(tx.pop(), Some(&1));
+            *tx.peek_mut().unwrap() = 20;
+            *tx.peek_mut().unwrap() = 200;
+            assert_eq!(format!("{:?}", tx.split_at(4)), "Some([4, 5])");
+            assert!(tx.split_when(|&elem| elem > 1000).is_none());
+            tx.merge((6..=7).collect());
+            assert_eq!(format!("{:?}", tx.split_off(3)), "Some([7])");
+            assert_eq!(format!("{:?}", tx.list()), "[200, 3, 6]");
+            assert_len(tx.list());
+        }
+        assert_eq!(format!("{:?}", list), "[1, 2, 3, 4, 5]");
+        assert_len(&list);
+    }
+
+    #[test]
+    fn test_transaction_commit() {
+        let mut list = (1..=3).collect::<List<_>>();
+        let mut tx = list.begin();
+        tx.pop();
+        tx.push(10);
+        tx.merge(List::from_iter([4]));
+        assert_eq!(format!("{:?}", tx.split_off(2)), "Some([3, 4])");
+        tx.pop();
+        let removed = tx.commit();
+        assert_eq!(format!("{:?}", list), "[2]");
+        assert_len(&list);
+        // removed values are handed back instead of being dropped
+        assert_eq!(removed.popped, vec![1, 10]);
+        assert_eq!(format!("{:?}", removed.split), "[[3, 4]]");
+    }
+
+    #[test]
+    fn test_nested_transaction() {
+        let mut list = (1..=3).collect::<List<_>>();
+        let mut tx = list.begin();
+        tx.push(0);
+        {
+            // rolled back on its own
+            let mut inner = tx.begin();
+            inner.pop();
+            inner.pop();
+            inner.rollback();
+        }
+        assert_eq!(tx.peek(), Some(&0));
+        {
+            // committed into the outer one, which is then rolled back
+            let mut inner = tx.begin();
+            inner.split_next(1);
+            // nothing handed back yet, outer one can still undo it
+            let removed = inner.commit();
+            assert!(removed.popped.is_empty() && removed.split.is_empty());
+        }
+        assert_eq!(format!("{:?}", tx.list()), "[0, 1]");
+        drop(tx);
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_len(&list);
+    }
+
+    #[test]
+    fn test_zipper() {
+        let mut zipper = List::from_iter(['a', 'b', 'c']).zipper();
+        assert_eq!(zipper.focus(), Some(&'a'));
+        assert!(!zipper.move_left());
+
+        assert!(zipper.move_right());
+        assert!(zipper.move_right());
+        assert_eq!((zipper.focus(), zipper.index()), (Some(&'c'), 2));
+        assert!(zipper.move_right());
+        assert_eq!(zipper.focus(), None);
+        assert!(!zipper.move_right());
+        assert_eq!(zipper.replace('x'), Err('x'));
+        assert_eq!(zipper.delete(), None);
+
+        zipper.insert('d');
+        assert_eq!((zipper.focus(), zipper.len()), (Some(&'d'), 4));
+        assert!(zipper.move_left());
+        assert!(zipper.move_left());
+        assert_eq!(zipper.replace('B'), Ok('b'));
+        *zipper.focus_mut().unwrap() = 'β';
+        assert!(zipper.move_right());
+        assert_eq!(zipper.delete(), Some('c'));
+        assert_eq!(zipper.focus(), Some(&'d'));
+
+        let list = zipper.into_list();
+        assert_eq!(format!("{:?}", list), "['a', 'β', 'd']");
+        assert_len(&list);
+
+        let mut empty = List::<i32>::new().zipper();
+        assert!(empty.is_empty());
+        assert!(!empty.move_left() && !empty.move_right());
+        assert!(empty.into_list().is_empty());
+    }
+
+    #[test]
+    fn test_find_sublist() {
+        let list = List::from_iter([1, 2, 1, 2, 1, 3, 1]);
+        assert_eq!(list.find_sublist(&[1, 2, 1, 3]), Some(2));
+        assert_eq!(list.find_sublist(&[1]), Some(0));
+        assert_eq!(list.find_sublist(&[3, 1]), Some(5));
+        assert_eq!(list.find_sublist(&[]), Some(0));
+        assert_eq!(list.find_sublist(&[1, 3, 1, 1]), None);
+        assert!(list.contains_sublist(&[2, 1, 2]));
+        assert!(!list.contains_sublist(&[2, 2]));
+        assert!(!List::new().contains_sublist(&[1]));
+
+        // would need to backtrack with naive matching
+        let list = List::from_iter("aaaaab".chars());
+        assert_eq!(list.find_sublist(&['a', 'a', 'b']), Some(3));
+    }
+
+    #[test]
+    fn test_split_on_sequence() {
+        let list = List::from_iter("ab--cd---e--".chars());
+        let pieces = list.split_on_sequence(&['-', '-']);
+        for piece in &pieces {
+            assert_len(piece);
+        }
+        let pieces = pieces
+            .iter()
+            .map(|piece| piece.iter().collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(pieces, ["ab", "cd", "-e", ""]);
+
+        let pieces = List::from_iter([1, 2, 3]).split_on_sequence(&[4]);
+        assert_eq!(format!("{:?}", pieces), "[[1, 2, 3]]");
+        let pieces = List::from_iter([1, 2, 3]).split_on_sequence(&[]);
+        assert_eq!(format!("{:?}", pieces), "[[1, 2, 3]]");
+        let pieces = List::<i32>::new().split_on_sequence(&[1]);
+        assert_eq!(format!("{:?}", pieces), "[[]]");
+    }
 }
@@ -0,0 +1,254 @@
+//! Classic single pass algorithms on List, which only relink the existing nodes (O(1) extra space)
+
+use std::mem;
+
+use super::{Link, List, Node};
+
+/// End of a list being built, so that nodes can be appended in O(1)
+struct Tail<'a, T> {
+    link: &'a mut Link<T>,
+    len: &'a mut usize,
+}
+
+impl<'a, T> Tail<'a, T> {
+    /// `list` has to be empty
+    fn new(list: &'a mut List<T>) -> Self {
+        debug_assert!(list.head.is_none(), "tail of non empty list");
+        Tail {
+            link: &mut list.head,
+            len: &mut list.len,
+        }
+    }
+
+    /// `node` must not be linked to anything
+    fn append(self, node: Box<Node<T>>) -> Self {
+        *self.len += 1;
+        Tail {
+            link: &mut self.link.insert(node).next,
+            len: self.len,
+        }
+    }
+
+    /// Moves all the nodes of `list` to the end, no need to go on appending after that
+    fn append_list(self, mut list: List<T>) {
+        *self.link = list.head.take();
+        *self.len += mem::take(&mut list.len);
+    }
+}
+
+impl<T> List<T> {
+    /// Middle element (tortoise and hare), of the two middle ones for even length it's the first
+    pub fn middle(&self) -> Option<&T> {
+        let mut slow = self.head.as_deref()?;
+        let mut fast = slow;
+        while let Some(next) = fast.next.as_deref().and_then(|node| node.next.as_deref()) {
+            slow = slow.next.as_deref()?;
+            fast = next;
+        }
+        Some(&slow.elem)
+    }
+
+    /// `k`th element counting from the tail, 0 is the tail itself
+    pub fn nth_from_end(&self, k: usize) -> Option<&T> {
+        // NOTE: lead is k + 1 nodes ahead of trail, so trail is at the right node when lead runs
+        // out
+        let mut lead = self.iter();
+        lead.nth(k)?;
+        let mut trail = self.iter();
+        for _ in lead {
+            trail.next();
+        }
+        trail.next()
+    }
+
+    /// Splits the list into the elements for which `pred` returned true and the rest, both keep
+    /// their order. `pred` is called once per element, in order
+    pub fn partition<F>(mut self, mut pred: F) -> (List<T>, List<T>)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut matched = List::new();
+        let mut rest = List::new();
+        let mut matched_tail = Tail::new(&mut matched);
+        let mut rest_tail = Tail::new(&mut rest);
+        while let Some(node) = self.pop_node() {
+            if pred(&node.elem) {
+                matched_tail = matched_tail.append(node);
+            } else {
+                rest_tail = rest_tail.append(node);
+            }
+        }
+        (matched, rest)
+    }
+
+    /// Alternates the nodes of this list and `other`, starting with this one. Whatever is left
+    /// of the longer one goes at the end
+    pub fn interleave(&mut self, mut other: List<T>) {
+        // NOTE: nodes are moved out and back in, instead of building a new list, so that the list
+        // keeps its node pool (if any)
+        let mut this = List::new();
+        this.head = self.head.take();
+        this.len = mem::take(&mut self.len);
+
+        let mut tail = Tail::new(self);
+        loop {
+            let Some(node) = this.pop_node() else {
+                return tail.append_list(other);
+            };
+            tail = tail.append(node);
+            let Some(node) = other.pop_node() else {
+                return tail.append_list(this);
+            };
+            tail = tail.append(node);
+        }
+    }
+
+    /// Splits the list into the elements at odd positions (1st, 3rd, ..., i.e. even indices) and
+    /// the ones at even positions
+    pub fn odd_even_split(self) -> (List<T>, List<T>) {
+        let mut odd = false;
+        self.partition(|_| {
+            odd = !odd;
+            odd
+        })
+    }
+}
+
+/// Split off (and reversed) second half of the list, which is put back when this is dropped
+struct ReversedHalf<'a, T> {
+    list: &'a mut List<T>,
+    half: List<T>,
+}
+
+impl<T> Drop for ReversedHalf<'_, T> {
+    fn drop(&mut self) {
+        let half = mem::take(&mut self.half);
+        self.list.merge(half.reversed());
+    }
+}
+
+impl<T: PartialEq> List<T> {
+    /// Reverses the second half in place to compare it with the first one, and then puts it back
+    /// (even if comparing the elements panics)
+    pub fn is_palindrome(&mut self) -> bool {
+        let half = self.len / 2;
+        // NOTE: middle element of odd length list stays in the first half, it doesn't need a pair
+        let Some(second) = self.split_off(self.len - half) else {
+            return true;
+        };
+        let reversed = ReversedHalf {
+            list: self,
+            half: second.reversed(),
+        };
+        let is_palindrome = reversed.list.iter().take(half).eq(reversed.half.iter());
+        drop(reversed);
+        is_palindrome
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic::{self, AssertUnwindSafe};
+
+    use super::super::{test::assert_len, List};
+
+    #[test]
+    fn test_middle() {
+        assert_eq!(List::<i32>::new().middle(), None);
+        assert_eq!(List::from_iter([1]).middle(), Some(&1));
+        assert_eq!(List::from_iter([1, 2]).middle(), Some(&1));
+        assert_eq!(List::from_iter([1, 2, 3]).middle(), Some(&2));
+        assert_eq!(List::from_iter([1, 2, 3, 4]).middle(), Some(&2));
+        assert_eq!(List::from_iter(1..=9).middle(), Some(&5));
+    }
+
+    #[test]
+    fn test_nth_from_end() {
+        let list = List::from_iter([1, 2, 3]);
+        assert_eq!(list.nth_from_end(0), Some(&3));
+        assert_eq!(list.nth_from_end(2), Some(&1));
+        assert_eq!(list.nth_from_end(3), None);
+        assert_eq!(List::<i32>::new().nth_from_end(0), None);
+    }
+
+    #[test]
+    fn test_is_palindrome() {
+        for (elems, expected) in [
+            ("", true),
+            ("a", true),
+            ("aa", true),
+            ("ab", false),
+            ("aba", true),
+            ("abba", true),
+            ("abca", false),
+            ("racecar", true),
+            ("racebar", false),
+        ] {
+            let mut list = List::from_iter(elems.chars());
+            assert_eq!(list.is_palindrome(), expected, "{}", elems);
+            // list is put back as it was
+            assert_eq!(list.iter().collect::<String>(), elems);
+            assert_len(&list);
+        }
+    }
+
+    #[test]
+    fn test_is_palindrome_panic() {
+        struct PanicOnEq(i32);
+
+        impl PartialEq for PanicOnEq {
+            fn eq(&self, _: &Self) -> bool {
+                panic!("eq");
+            }
+        }
+
+        let mut list = List::from_iter([1, 2, 3, 4].map(PanicOnEq));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| list.is_palindrome()));
+        assert!(result.is_err());
+        assert_eq!(list.iter().map(|elem| elem.0).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_len(&list);
+    }
+
+    #[test]
+    fn test_partition() {
+        let (even, odd) = List::from_iter(1..=7).partition(|elem| elem % 2 == 0);
+        assert_eq!(format!("{:?} {:?}", even, odd), "[2, 4, 6] [1, 3, 5, 7]");
+        assert_len(&even);
+        assert_len(&odd);
+
+        let (all, none) = List::from_iter(1..=2).partition(|_| true);
+        assert_eq!(format!("{:?} {:?}", all, none), "[1, 2] []");
+        assert_len(&none);
+    }
+
+    #[test]
+    fn test_interleave() {
+        let mut list = List::from_iter([1, 3, 5, 7]);
+        list.interleave(List::from_iter([2, 4]));
+        assert_eq!(format!("{:?}", list), "[1, 2, 3, 4, 5, 7]");
+        assert_len(&list);
+
+        let mut list = List::from_iter([1]);
+        list.interleave(List::from_iter([2, 4, 6]));
+        assert_eq!(format!("{:?}", list), "[1, 2, 4, 6]");
+        assert_len(&list);
+
+        let mut list = List::new();
+        list.interleave(List::from_iter([1, 2]));
+        assert_eq!(format!("{:?}", list), "[1, 2]");
+        list.interleave(List::new());
+        assert_eq!(format!("{:?}", list), "[1, 2]");
+        assert_len(&list);
+    }
+
+    #[test]
+    fn test_odd_even_split() {
+        let (odd, even) = List::from_iter('a'..='e').odd_even_split();
+        assert_eq!(format!("{:?} {:?}", odd, even), "['a', 'c', 'e'] ['b', 'd']");
+        assert_len(&odd);
+        assert_len(&even);
+
+        let (odd, even) = List::<i32>::new().odd_even_split();
+        assert!(odd.is_empty() && even.is_empty());
+    }
+}
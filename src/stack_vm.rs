@@ -0,0 +1,370 @@
+use std::fmt::Display;
+
+use crate::first::List;
+
+/// Instructions understood by the Vm
+///
+/// Jumps and calls take their target (index into the program) from the top of operand stack, so
+/// they can be written in reverse-Polish too, e.g. `0 7 jz` jumps to 7th instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Push(i64),
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Dup,
+    Swap,
+    Drop,
+    /// pops n, and then drops n values off the stack at once
+    DropN,
+    /// pops n, and then drops n values under the top one (e.g. the locals under a result)
+    Nip,
+    /// pops target and jumps to it
+    Jump,
+    /// pops target, then condition, and jumps to target if condition is 0
+    JumpIfZero,
+    /// pops target, pushes the next instruction on the return stack and jumps to target
+    Call,
+    /// pops the return stack and jumps back to it
+    Ret,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    StackUnderflow,
+    ReturnStackUnderflow,
+    DivisionByZero,
+    Overflow,
+    InvalidJump(i64),
+    /// negative count for dropn or nip
+    InvalidCount(i64),
+    /// program ran more instructions than it was allowed to, see Vm::run_with_limit()
+    StepLimitExceeded,
+    UnknownToken(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::StackUnderflow => write!(f, "stack underflow"),
+            Error::ReturnStackUnderflow => write!(f, "return stack underflow"),
+            Error::DivisionByZero => write!(f, "division by zero"),
+            Error::Overflow => write!(f, "arithmetic overflow"),
+            Error::InvalidJump(target) => write!(f, "invalid jump target {}", target),
+            Error::InvalidCount(count) => write!(f, "invalid count {}", count),
+            Error::StepLimitExceeded => write!(f, "step limit exceeded"),
+            Error::UnknownToken(token) => write!(f, "unknown token '{}'", token),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Turns whitespace separated reverse-Polish expression into the program
+pub fn tokenize(src: &str) -> Result<Vec<Op>, Error> {
+    src.split_whitespace()
+        .map(|token| {
+            Ok(match token {
+                "+" => Op::Add,
+                "-" => Op::Sub,
+                "*" => Op::Mul,
+                "/" => Op::Div,
+                "%" => Op::Rem,
+                "dup" => Op::Dup,
+                "swap" => Op::Swap,
+                "drop" => Op::Drop,
+                "dropn" => Op::DropN,
+                "nip" => Op::Nip,
+                "jmp" => Op::Jump,
+                "jz" => Op::JumpIfZero,
+                "call" => Op::Call,
+                "ret" => Op::Ret,
+                _ => Op::Push(
+                    token
+                        .parse()
+                        .map_err(|_| Error::UnknownToken(token.to_string()))?,
+                ),
+            })
+        })
+        .collect()
+}
+
+/// Number of instructions eval() runs before giving up, so that looping programs return too
+pub const EVAL_STEP_LIMIT: usize = 1_000_000;
+
+/// Evaluates the reverse-Polish expression and returns what's left on top of the stack
+pub fn eval(src: &str) -> Result<i64, Error> {
+    let program = tokenize(src)?;
+    let mut vm = Vm::new();
+    vm.run_with_limit(&program, EVAL_STEP_LIMIT)?;
+    vm.stack.pop().ok_or(Error::StackUnderflow)
+}
+
+/// Stack machine with first::List as both, the operand stack and the return stack
+#[derive(Default)]
+pub struct Vm {
+    stack: List<i64>,
+    returns: List<usize>,
+}
+
+impl Vm {
+    pub fn new() -> Self {
+        Self {
+            stack: List::new(),
+            returns: List::new(),
+        }
+    }
+
+    /// Runs the program till the instruction pointer goes past its last instruction
+    /// Operand stack is left as it was at the end (or at the failing instruction), so that the
+    /// result can be looked at with stack()
+    ///
+    /// Jumps can go backwards, so this never returns for a program which loops forever, see
+    /// run_with_limit()
+    pub fn run(&mut self, program: &[Op]) -> Result<(), Error> {
+        self.run_with_limit(program, usize::MAX)
+    }
+
+    /// Same as run(), but fails with StepLimitExceeded instead of running more than `max_steps`
+    /// instructions
+    pub fn run_with_limit(&mut self, program: &[Op], max_steps: usize) -> Result<(), Error> {
+        let mut pc = 0;
+        let mut steps = 0;
+        while let Some(&op) = program.get(pc) {
+            if steps == max_steps {
+                return Err(Error::StepLimitExceeded);
+            }
+            steps += 1;
+            pc += 1;
+            match op {
+                Op::Push(value) => self.stack.push(value),
+                Op::Add => self.binary(i64::checked_add)?,
+                Op::Sub => self.binary(i64::checked_sub)?,
+                Op::Mul => self.binary(i64::checked_mul)?,
+                Op::Div => {
+                    self.check_divisor()?;
+                    self.binary(i64::checked_div)?;
+                },
+                Op::Rem => {
+                    self.check_divisor()?;
+                    self.binary(i64::checked_rem)?;
+                },
+                Op::Dup => {
+                    let top = *self.stack.peek().ok_or(Error::StackUnderflow)?;
+                    self.stack.push(top);
+                },
+                Op::Swap => {
+                    let b = self.pop()?;
+                    let a = self.pop()?;
+                    self.stack.push(b);
+                    self.stack.push(a);
+                },
+                Op::Drop => {
+                    self.pop()?;
+                },
+                Op::DropN => {
+                    let count = self.pop_count()?;
+                    // NOTE: split_off() leaves the top `count` values in stack, so the rest is
+                    // what becomes the stack
+                    let rest = self.stack.split_off(count).ok_or(Error::StackUnderflow)?;
+                    self.stack = rest;
+                },
+                Op::Nip => {
+                    let count = self.pop_count()?;
+                    let mut dropped = self.stack.split_off(1).ok_or(Error::StackUnderflow)?;
+                    match dropped.split_off(count) {
+                        Some(rest) => self.stack.merge(rest),
+                        None => {
+                            // put the stack back as it was
+                            self.stack.merge(dropped);
+                            return Err(Error::StackUnderflow);
+                        },
+                    }
+                },
+                Op::Jump => pc = self.pop_target(program)?,
+                Op::JumpIfZero => {
+                    let target = self.pop_target(program)?;
+                    if self.pop()? == 0 {
+                        pc = target;
+                    }
+                },
+                Op::Call => {
+                    let target = self.pop_target(program)?;
+                    self.returns.push(pc);
+                    pc = target;
+                },
+                Op::Ret => pc = self.returns.pop().ok_or(Error::ReturnStackUnderflow)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Operand stack from top to bottom
+    pub fn stack(&self) -> impl Iterator<Item = &i64> {
+        self.stack.iter()
+    }
+
+    fn pop(&mut self) -> Result<i64, Error> {
+        self.stack.pop().ok_or(Error::StackUnderflow)
+    }
+
+    fn pop_count(&mut self) -> Result<usize, Error> {
+        let count = self.pop()?;
+        usize::try_from(count).map_err(|_| Error::InvalidCount(count))
+    }
+
+    fn pop_target(&mut self, program: &[Op]) -> Result<usize, Error> {
+        let target = self.pop()?;
+        // NOTE: jumping right past the last instruction is allowed, that's how program halts
+        match usize::try_from(target) {
+            Ok(pc) if pc <= program.len() => Ok(pc),
+            _ => Err(Error::InvalidJump(target)),
+        }
+    }
+
+    /// Top two values as `(a, b)`, `b` being the top, without popping them
+    // NOTE: operands are only popped once the instruction can't fail anymore, so that a failing
+    // instruction leaves the stack as it was
+    fn operands(&self) -> Result<(i64, i64), Error> {
+        let mut top = self.stack.iter();
+        match (top.next(), top.next()) {
+            (Some(&b), Some(&a)) => Ok((a, b)),
+            _ => Err(Error::StackUnderflow),
+        }
+    }
+
+    fn check_divisor(&self) -> Result<(), Error> {
+        match self.operands()? {
+            (_, 0) => Err(Error::DivisionByZero),
+            _ => Ok(()),
+        }
+    }
+
+    /// Pops `b` then `a` and pushes `op(a, b)`
+    fn binary(&mut self, op: fn(i64, i64) -> Option<i64>) -> Result<(), Error> {
+        let (a, b) = self.operands()?;
+        let result = op(a, b).ok_or(Error::Overflow)?;
+        self.stack.pop();
+        self.stack.pop();
+        self.stack.push(result);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{eval, tokenize, Error, Op, Vm};
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("1 -2 + dup swap drop jz"),
+            Ok(vec![
+                Op::Push(1),
+                Op::Push(-2),
+                Op::Add,
+                Op::Dup,
+                Op::Swap,
+                Op::Drop,
+                Op::JumpIfZero,
+            ])
+        );
+        assert_eq!(tokenize("1 x"), Err(Error::UnknownToken("x".to_string())));
+    }
+
+    #[test]
+    fn test_eval() {
+        assert_eq!(eval("3 4 + 2 *"), Ok(14));
+        assert_eq!(eval("5 1 2 + 4 * + 3 -"), Ok(14));
+        assert_eq!(eval("7 2 /"), Ok(3));
+        assert_eq!(eval("7 2 %"), Ok(1));
+        assert_eq!(eval("1 2 swap -"), Ok(1));
+        assert_eq!(eval("3 dup *"), Ok(9));
+        assert_eq!(eval("1 2 drop"), Ok(1));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(eval(""), Err(Error::StackUnderflow));
+        assert_eq!(eval("1 +"), Err(Error::StackUnderflow));
+        assert_eq!(eval("1 0 /"), Err(Error::DivisionByZero));
+        assert_eq!(eval("1 0 %"), Err(Error::DivisionByZero));
+        assert_eq!(eval("9223372036854775807 1 +"), Err(Error::Overflow));
+        assert_eq!(eval("-9223372036854775808 -1 /"), Err(Error::Overflow));
+        assert_eq!(eval("-1 jmp"), Err(Error::InvalidJump(-1)));
+        assert_eq!(eval("ret"), Err(Error::ReturnStackUnderflow));
+    }
+
+    #[test]
+    fn test_jumps() {
+        // count down from 5, leaving every value on the stack
+        //  0: 5
+        //  1: dup 1 - (loop start)
+        //  4: dup 9 jz (done when 0)
+        //  7: 1 jmp
+        let program = tokenize("5 dup 1 - dup 9 jz 1 jmp").unwrap();
+        let mut vm = Vm::new();
+        assert_eq!(vm.run(&program), Ok(()));
+        assert_eq!(vm.stack().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_call_ret() {
+        //  0: 3 7 call (square)
+        //  3: 1 + 10 jmp (halt)
+        //  7: dup * ret (square)
+        let program = tokenize("3 7 call 1 + 10 jmp dup * ret").unwrap();
+        let mut vm = Vm::new();
+        assert_eq!(vm.run(&program), Ok(()));
+        assert_eq!(vm.stack().copied().collect::<Vec<_>>(), vec![10]);
+    }
+
+    #[test]
+    fn test_dropn_nip() {
+        assert_eq!(eval("1 2 3 4 2 dropn"), Ok(2));
+        assert_eq!(eval("1 2 0 dropn"), Ok(2));
+        assert_eq!(eval("1 2 3 4 2 nip"), Ok(4));
+        assert_eq!(eval("1 2 3 4 2 nip drop"), Ok(1));
+        assert_eq!(eval("1 2 3 dropn"), Err(Error::StackUnderflow));
+        assert_eq!(eval("1 -1 dropn"), Err(Error::InvalidCount(-1)));
+
+        // stack is left as it was when there is not enough to nip
+        let program = tokenize("1 2 3 5 nip").unwrap();
+        let mut vm = Vm::new();
+        assert_eq!(vm.run(&program), Err(Error::StackUnderflow));
+        assert_eq!(vm.stack().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_failed_operands_kept() {
+        // missing operand is reported before the divisor is looked at
+        assert_eq!(eval("0 /"), Err(Error::StackUnderflow));
+        assert_eq!(eval("0 %"), Err(Error::StackUnderflow));
+
+        for (src, err, stack) in [
+            ("0 /", Error::StackUnderflow, vec![0]),
+            ("5 +", Error::StackUnderflow, vec![5]),
+            ("1 0 /", Error::DivisionByZero, vec![0, 1]),
+            ("9223372036854775807 1 +", Error::Overflow, vec![1, i64::MAX]),
+        ] {
+            let program = tokenize(src).unwrap();
+            let mut vm = Vm::new();
+            assert_eq!(vm.run(&program), Err(err));
+            assert_eq!(vm.stack().copied().collect::<Vec<_>>(), stack, "{src}");
+        }
+    }
+
+    #[test]
+    fn test_step_limit() {
+        assert_eq!(eval("0 jmp"), Err(Error::StepLimitExceeded));
+
+        let program = tokenize("1 2 +").unwrap();
+        let mut vm = Vm::new();
+        assert_eq!(vm.run_with_limit(&program, 2), Err(Error::StepLimitExceeded));
+        assert_eq!(vm.stack().copied().collect::<Vec<_>>(), vec![2, 1]);
+        let mut vm = Vm::new();
+        assert_eq!(vm.run_with_limit(&program, 3), Ok(()));
+    }
+}
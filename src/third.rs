@@ -47,7 +47,7 @@ pub struct Iter<'a, T> {
 }
 
 impl<T: std::fmt::Debug> List<T> {
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             next: self.head.as_deref(),
             // as_deref() will work same the way it worked for Box<>